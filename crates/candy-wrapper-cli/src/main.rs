@@ -0,0 +1,719 @@
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id, token_2022::spl_token_2022,
+};
+use anyhow::{anyhow, Result};
+use candy_wrapper_client::{
+    instructions::{self, ProtocolFeeClaim},
+    pda,
+    rpc::{
+        fetch_closable_accounts, fetch_reserve_attestation, fetch_token_program,
+        fetch_transfer_hook_accounts, fetch_withheld_token_accounts,
+    },
+    state::{decode_legacy_authority, fee_payout},
+    CreateMintArgs, FeePayout, TransferFeeArgs, TransferHookArgs,
+};
+use clap::{Parser, Subcommand};
+use solana_sdk::{
+    hash::hash,
+    instruction::AccountMeta,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair},
+    signer::Signer,
+};
+
+mod rpc;
+
+use rpc::{MintState, Rpc};
+
+// token metadata account data besides its strings: update authority, mint, the length
+// prefixes of name, symbol and uri, an empty additional metadata vector and the TLV header
+const TOKEN_METADATA_FIXED_LEN: usize = 32 + 32 + 4 + 4 + 4 + 4 + 4;
+
+#[derive(Parser)]
+#[command(name = "candy-wrapper", about = "Manage candy wrapper tokens")]
+struct Cli {
+    /// RPC URL of the cluster
+    #[arg(long, short, global = true, default_value = "http://localhost:8899")]
+    url: String,
+    /// Keypair paying for and signing transactions, defaults to the Solana CLI keypair
+    #[arg(long, short, global = true)]
+    keypair: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a mint backed by `base_coin` and administered by the payer, together with its
+    /// metadata
+    CreateMint {
+        #[arg(long)]
+        base_coin: Pubkey,
+        #[arg(long)]
+        mint_to_base_ratio: u16,
+        #[arg(long, default_value_t = 0)]
+        issuance_fee_basis_pts: u16,
+        #[arg(long, default_value_t = 0)]
+        redemption_fee_basis_pts: u16,
+        #[arg(long, default_value_t = 0)]
+        transfer_fee_basis_pts: u16,
+        #[arg(long, default_value_t = u64::MAX)]
+        max_transfer_fee: u64,
+        /// Defaults to the payer
+        #[arg(long)]
+        fee_collector: Option<Pubkey>,
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        symbol: String,
+        #[arg(long)]
+        uri: String,
+        /// Keypair of the new mint, a random one is generated otherwise
+        #[arg(long, conflicts_with = "mint_seed")]
+        mint_keypair: Option<String>,
+        /// Seed deriving the new mint's address from the payer, for a deterministic address. It is
+        /// hashed, so any length works
+        #[arg(long)]
+        mint_seed: Option<String>,
+        /// Program invoked on every transfer of the token, which the admin can change later
+        #[arg(long)]
+        transfer_hook_program: Option<Pubkey>,
+        /// Only allow transfers to or from wallets registered with `add-merchant`, for good
+        #[arg(long, conflicts_with = "transfer_hook_program")]
+        gift_card: bool,
+        /// Stop wallets blocked by the compliance officer from transferring the token
+        #[arg(long, conflicts_with = "transfer_hook_program")]
+        blocklist: bool,
+        /// Let the compliance officer freeze and thaw token accounts
+        #[arg(long)]
+        freezable: bool,
+        /// Create token accounts frozen, until the compliance officer thaws them
+        #[arg(long, requires = "freezable")]
+        default_frozen: bool,
+        /// Stop holders from transferring the token, replacing the transfer fee
+        #[arg(long, conflicts_with_all = ["transfer_fee_basis_pts", "max_transfer_fee"])]
+        non_transferable: bool,
+    },
+    /// Deposit base coin to issue tokens
+    Issue {
+        #[arg(long)]
+        mint: Pubkey,
+        /// Base coin amount, in base units
+        amount: u64,
+    },
+    /// Burn tokens to redeem base coin
+    Redeem {
+        #[arg(long)]
+        mint: Pubkey,
+        /// Token amount, in base units
+        amount: u64,
+    },
+    /// Harvest withheld transfer fees from holder accounts and withdraw them
+    Harvest {
+        #[arg(long)]
+        mint: Pubkey,
+        /// Holder accounts harvested per transaction
+        #[arg(long, default_value_t = 10)]
+        batch_size: usize,
+    },
+    /// Withdraw transfer fees withheld in the mint and fees held in the fee vault
+    WithdrawFees {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Change issuance, redemption and transfer fees
+    SetFees {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        issuance_fee_basis_pts: Option<u16>,
+        #[arg(long)]
+        redemption_fee_basis_pts: Option<u16>,
+        #[arg(long, requires = "max_transfer_fee")]
+        transfer_fee_basis_pts: Option<u16>,
+        #[arg(long, requires = "transfer_fee_basis_pts")]
+        max_transfer_fee: Option<u64>,
+    },
+    /// Change the program invoked on every transfer of the token, or remove it
+    SetTransferHook {
+        #[arg(long)]
+        mint: Pubkey,
+        /// Removes the hook if omitted
+        program: Option<Pubkey>,
+    },
+    /// Allow holders of a gift card token to spend it at `wallet`
+    AddMerchant {
+        #[arg(long)]
+        mint: Pubkey,
+        wallet: Pubkey,
+    },
+    /// Stop holders of a gift card token from spending it at `wallet`
+    RemoveMerchant {
+        #[arg(long)]
+        mint: Pubkey,
+        wallet: Pubkey,
+    },
+    /// Change the wallet allowed to block and unblock holders
+    SetComplianceOfficer {
+        #[arg(long)]
+        mint: Pubkey,
+        officer: Pubkey,
+    },
+    /// Block `wallet` from issuing, redeeming and, with `--blocklist`, transferring the token,
+    /// signed by the compliance officer
+    Block {
+        #[arg(long)]
+        mint: Pubkey,
+        wallet: Pubkey,
+    },
+    /// Lift the block on `wallet`, signed by the compliance officer
+    Unblock {
+        #[arg(long)]
+        mint: Pubkey,
+        wallet: Pubkey,
+    },
+    /// Freeze the wallet's token account, signed by the compliance officer
+    Freeze {
+        #[arg(long)]
+        mint: Pubkey,
+        wallet: Pubkey,
+    },
+    /// Thaw the wallet's token account, signed by the compliance officer
+    Thaw {
+        #[arg(long)]
+        mint: Pubkey,
+        wallet: Pubkey,
+    },
+    /// Change the wallet receiving the creator's share of fees
+    SetFeeCollector {
+        #[arg(long)]
+        mint: Pubkey,
+        fee_collector: Pubkey,
+    },
+    /// Permanently lock the mint's configuration
+    MakeImmutable {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Close the mint and its accounts once its supply is redeemed, sweeping the dust left in the
+    /// reserves to the fee collector
+    Close {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Bring the `Authority` account of a mint created before the fee vault was added up to
+    /// date
+    Migrate {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Show the mint's configuration, reserves, supply and backing
+    Show {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Record the mint's current reserves, supply and backing on chain
+    Attest {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// List the reserve attestations still held on chain, oldest first
+    Attestations {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+}
+
+fn read_keypair(path: &str) -> Result<Keypair> {
+    read_keypair_file(path).map_err(|err| anyhow!("failed to read keypair {path}: {err}"))
+}
+
+fn default_keypair_path() -> String {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    format!("{home}/.config/solana/id.json")
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let payer = read_keypair(&cli.keypair.unwrap_or_else(default_keypair_path))?;
+    let rpc = Rpc::new(cli.url, payer);
+    let payer = rpc.payer.pubkey();
+
+    match cli.command {
+        Command::CreateMint {
+            base_coin,
+            mint_to_base_ratio,
+            issuance_fee_basis_pts,
+            redemption_fee_basis_pts,
+            transfer_fee_basis_pts,
+            max_transfer_fee,
+            fee_collector,
+            name,
+            symbol,
+            uri,
+            mint_keypair,
+            mint_seed,
+            transfer_hook_program,
+            gift_card,
+            blocklist,
+            freezable,
+            default_frozen,
+            non_transferable,
+        } => {
+            let mint_seed = mint_seed.map(|seed| hash(seed.as_bytes()).to_bytes());
+            let (mint, mint_keypair) = match mint_seed {
+                Some(seed) => (pda::mint(&payer, &seed).0, None),
+                None => {
+                    let mint_keypair = match mint_keypair {
+                        Some(path) => read_keypair(&path)?,
+                        None => Keypair::new(),
+                    };
+                    (mint_keypair.pubkey(), Some(mint_keypair))
+                }
+            };
+            let token_program_base_coin = fetch_token_program(&rpc.client, &base_coin)?;
+
+            let signature = rpc.send(
+                &[instructions::create_mint(
+                    &payer,
+                    &mint,
+                    &token_program_base_coin,
+                    CreateMintArgs {
+                        admin: payer,
+                        base_coin,
+                        mint_to_base_ratio,
+                        issuance_fee_basis_pts,
+                        redemption_fee_basis_pts,
+                        fee_collector: fee_collector.unwrap_or(payer),
+                        transfer_fee_args: if non_transferable {
+                            TransferFeeArgs {
+                                fee_basis_pts: 0,
+                                max_fee: 0,
+                            }
+                        } else {
+                            TransferFeeArgs {
+                                fee_basis_pts: transfer_fee_basis_pts,
+                                max_fee: max_transfer_fee,
+                            }
+                        },
+                        transfer_hook_args: transfer_hook_program.map(|program_id| {
+                            TransferHookArgs {
+                                program_id,
+                                authority: pda::authority(&mint).0,
+                            }
+                        }),
+                        gift_card,
+                        blocklist,
+                        freezable,
+                        default_frozen,
+                        non_transferable,
+                        mint_seed,
+                    },
+                )],
+                &mint_keypair.iter().collect::<Vec<_>>(),
+            )?;
+            println!("Created mint {mint}: {signature}");
+
+            let mint_account = rpc.client.get_account(&mint)?;
+            let metadata_len = TOKEN_METADATA_FIXED_LEN + name.len() + symbol.len() + uri.len();
+            let lamports = rpc
+                .client
+                .get_minimum_balance_for_rent_exemption(mint_account.data.len() + metadata_len)?
+                .saturating_sub(mint_account.lamports);
+            let signature = rpc.send(
+                &[instructions::create_mint_metadata(
+                    &payer, &mint, lamports, name, symbol, uri,
+                )],
+                &[],
+            )?;
+            println!("Created metadata: {signature}");
+        }
+        Command::Issue { mint, amount } => {
+            let state = rpc.mint_state(&mint)?;
+            let base_coin = state.authority.base_coin;
+            let mut ix = instructions::issue_mint(
+                &payer,
+                &mint,
+                &base_coin,
+                &state.token_program_base_coin,
+                amount,
+            );
+            ix.accounts.extend(fetch_transfer_hook_accounts(
+                &rpc.client,
+                &base_coin,
+                &instructions::issue_mint_transfers(
+                    &payer,
+                    &mint,
+                    &base_coin,
+                    &state.token_program_base_coin,
+                ),
+                amount,
+            )?);
+            let signature = rpc.send(&[ix], &[])?;
+            println!("Issued: {signature}");
+        }
+        Command::Redeem { mint, amount } => {
+            let state = rpc.mint_state(&mint)?;
+            let base_coin = state.authority.base_coin;
+            let mut ix = instructions::redeem_basecoin(
+                &payer,
+                &mint,
+                &base_coin,
+                &state.token_program_base_coin,
+                amount,
+            );
+            ix.accounts.extend(fetch_transfer_hook_accounts(
+                &rpc.client,
+                &base_coin,
+                &instructions::redeem_basecoin_transfers(
+                    &payer,
+                    &mint,
+                    &base_coin,
+                    &state.token_program_base_coin,
+                ),
+                0,
+            )?);
+            let signature = rpc.send(&[ix], &[])?;
+            println!("Redeemed: {signature}");
+        }
+        Command::Harvest { mint, batch_size } => {
+            let state = rpc.mint_state(&mint)?;
+            let hook_accounts = withdraw_fees_hook_accounts(&rpc, &mint, &state)?;
+            let holders = fetch_withheld_token_accounts(&rpc.client, &mint)?;
+            if holders.is_empty() {
+                println!("No withheld fees to harvest");
+            }
+            for batch in holders.chunks(batch_size.max(1)) {
+                let token_accounts: Vec<Pubkey> = batch.iter().map(|(key, _)| *key).collect();
+                let mut ix = instructions::harvest_and_withdraw_fees(
+                    &payer,
+                    &mint,
+                    &state.authority.base_coin,
+                    &state.token_program_base_coin,
+                    &state.authority.fee_collector,
+                    fee_payout(&state.authority),
+                    &token_accounts,
+                );
+                ix.accounts.extend(hook_accounts.iter().cloned());
+                let signature = rpc.send(&[ix], &[])?;
+                let harvested: u64 = batch.iter().map(|(_, withheld)| withheld).sum();
+                println!(
+                    "Harvested {harvested} from {} accounts: {signature}",
+                    batch.len()
+                );
+            }
+        }
+        Command::WithdrawFees { mint } => {
+            let state = rpc.mint_state(&mint)?;
+            let mut ix = instructions::withdraw_fees(
+                &payer,
+                &mint,
+                &state.authority.base_coin,
+                &state.token_program_base_coin,
+                &state.authority.fee_collector,
+                fee_payout(&state.authority),
+            );
+            ix.accounts
+                .extend(withdraw_fees_hook_accounts(&rpc, &mint, &state)?);
+            let signature = rpc.send(&[ix], &[])?;
+            println!("Withdrew fees: {signature}");
+        }
+        Command::SetFees {
+            mint,
+            issuance_fee_basis_pts,
+            redemption_fee_basis_pts,
+            transfer_fee_basis_pts,
+            max_transfer_fee,
+        } => {
+            let mut ixs = vec![];
+            if let Some(fee_basis_pts) = issuance_fee_basis_pts {
+                ixs.push(instructions::change_issuance_fee(
+                    &payer,
+                    &mint,
+                    fee_basis_pts,
+                ));
+            }
+            if let Some(fee_basis_pts) = redemption_fee_basis_pts {
+                ixs.push(instructions::change_redemption_fee(
+                    &payer,
+                    &mint,
+                    fee_basis_pts,
+                ));
+            }
+            if let (Some(fee_basis_pts), Some(max_fee)) = (transfer_fee_basis_pts, max_transfer_fee)
+            {
+                ixs.push(instructions::change_transfer_fee(
+                    &payer,
+                    &mint,
+                    fee_basis_pts,
+                    max_fee,
+                ));
+            }
+            if ixs.is_empty() {
+                return Err(anyhow!("no fee to change"));
+            }
+            let signature = rpc.send(&ixs, &[])?;
+            println!("Changed fees: {signature}");
+        }
+        Command::SetTransferHook { mint, program } => {
+            let signature = rpc.send(
+                &[instructions::change_transfer_hook(&payer, &mint, program)],
+                &[],
+            )?;
+            println!("Changed transfer hook: {signature}");
+        }
+        Command::AddMerchant { mint, wallet } => {
+            let signature = rpc.send(&[instructions::add_merchant(&payer, &mint, wallet)], &[])?;
+            println!("Added merchant: {signature}");
+        }
+        Command::RemoveMerchant { mint, wallet } => {
+            let signature =
+                rpc.send(&[instructions::remove_merchant(&payer, &mint, wallet)], &[])?;
+            println!("Removed merchant: {signature}");
+        }
+        Command::SetComplianceOfficer { mint, officer } => {
+            let signature = rpc.send(
+                &[instructions::change_compliance_officer(
+                    &payer, &mint, officer,
+                )],
+                &[],
+            )?;
+            println!("Changed compliance officer: {signature}");
+        }
+        Command::Block { mint, wallet } => {
+            let signature = rpc.send(&[instructions::block_wallet(&payer, &mint, wallet)], &[])?;
+            println!("Blocked {wallet}: {signature}");
+        }
+        Command::Unblock { mint, wallet } => {
+            let signature =
+                rpc.send(&[instructions::unblock_wallet(&payer, &mint, wallet)], &[])?;
+            println!("Unblocked {wallet}: {signature}");
+        }
+        Command::Freeze { mint, wallet } => {
+            let token_account =
+                get_associated_token_address_with_program_id(&wallet, &mint, &spl_token_2022::ID);
+            let signature = rpc.send(
+                &[instructions::freeze_account(&payer, &mint, &token_account)],
+                &[],
+            )?;
+            println!("Froze {token_account}: {signature}");
+        }
+        Command::Thaw { mint, wallet } => {
+            let token_account =
+                get_associated_token_address_with_program_id(&wallet, &mint, &spl_token_2022::ID);
+            let signature = rpc.send(
+                &[instructions::thaw_account(&payer, &mint, &token_account)],
+                &[],
+            )?;
+            println!("Thawed {token_account}: {signature}");
+        }
+        Command::SetFeeCollector {
+            mint,
+            fee_collector,
+        } => {
+            let signature = rpc.send(
+                &[instructions::change_fee_collector(
+                    &payer,
+                    &mint,
+                    fee_collector,
+                )],
+                &[],
+            )?;
+            println!("Changed fee collector: {signature}");
+        }
+        Command::MakeImmutable { mint } => {
+            let signature = rpc.send(&[instructions::set_to_immutable(&payer, &mint)], &[])?;
+            println!("Made immutable: {signature}");
+        }
+        Command::Close { mint } => {
+            let state = rpc.mint_state(&mint)?;
+            let base_coin = &state.authority.base_coin;
+            let token_program_base_coin = &state.token_program_base_coin;
+            let fee_collector = &state.authority.fee_collector;
+            // empty the fee vault first, which closing requires
+            let mut withdraw_fees = instructions::withdraw_fees(
+                &payer,
+                &mint,
+                base_coin,
+                token_program_base_coin,
+                fee_collector,
+                fee_payout(&state.authority),
+            );
+            withdraw_fees
+                .accounts
+                .extend(withdraw_fees_hook_accounts(&rpc, &mint, &state)?);
+            let claim = ProtocolFeeClaim {
+                mint,
+                base_coin: *base_coin,
+                token_program_base_coin: *token_program_base_coin,
+            };
+            let mut claim_protocol_fees =
+                instructions::claim_protocol_fees(&payer, std::slice::from_ref(&claim));
+            claim_protocol_fees
+                .accounts
+                .extend(fetch_transfer_hook_accounts(
+                    &rpc.client,
+                    base_coin,
+                    &instructions::claim_protocol_fees_transfers(&claim),
+                    0,
+                )?);
+            let closable = fetch_closable_accounts(&rpc.client, &mint)?;
+            let mut close_account = instructions::close_account(
+                &payer,
+                &mint,
+                base_coin,
+                token_program_base_coin,
+                fee_collector,
+                &closable,
+            );
+            close_account.accounts.extend(fetch_transfer_hook_accounts(
+                &rpc.client,
+                base_coin,
+                &instructions::close_account_transfers(
+                    &mint,
+                    base_coin,
+                    token_program_base_coin,
+                    fee_collector,
+                    &closable,
+                ),
+                0,
+            )?);
+            let signature = rpc.send(&[withdraw_fees, claim_protocol_fees, close_account], &[])?;
+            println!("Closed: {signature}");
+        }
+        Command::Migrate { mint } => {
+            let data = rpc.client.get_account_data(&pda::authority(&mint).0)?;
+            let authority = decode_legacy_authority(&data)
+                .map_err(|err| anyhow!("authority account is not in the legacy layout: {err}"))?;
+            let token_program_base_coin = fetch_token_program(&rpc.client, &authority.base_coin)?;
+            let signature = rpc.send(
+                &[instructions::migrate_authority(
+                    &payer,
+                    &mint,
+                    &authority.base_coin,
+                    &token_program_base_coin,
+                )],
+                &[],
+            )?;
+            println!("Migrated: {signature}");
+        }
+        Command::Show { mint } => show(&rpc, &mint)?,
+        Command::Attest { mint } => {
+            let state = rpc.mint_state(&mint)?;
+            let signature = rpc.send(
+                &[instructions::attest_reserves(
+                    &payer,
+                    &mint,
+                    &state.authority.base_coin,
+                    &state.token_program_base_coin,
+                )],
+                &[],
+            )?;
+            println!("Attested reserves: {signature}");
+        }
+        Command::Attestations { mint } => attestations(&rpc, &mint)?,
+    }
+
+    Ok(())
+}
+
+// accounts of the transfer hooks of the base coin and, if fees are paid out in it, of `mint`
+// for withdrawing the fees of `mint`
+fn withdraw_fees_hook_accounts(
+    rpc: &Rpc,
+    mint: &Pubkey,
+    state: &MintState,
+) -> Result<Vec<AccountMeta>> {
+    let mut accounts = fetch_transfer_hook_accounts(
+        &rpc.client,
+        &state.authority.base_coin,
+        &instructions::withdraw_fees_transfers(
+            mint,
+            &state.authority.base_coin,
+            &state.token_program_base_coin,
+            &state.authority.fee_collector,
+        ),
+        0,
+    )?;
+    if fee_payout(&state.authority) == FeePayout::Mint {
+        accounts.extend(fetch_transfer_hook_accounts(
+            &rpc.client,
+            mint,
+            &instructions::withdraw_fees_mint_transfers(mint, &state.authority.fee_collector),
+            0,
+        )?);
+    }
+    Ok(accounts)
+}
+
+fn show(rpc: &Rpc, mint: &Pubkey) -> Result<()> {
+    let state = rpc.mint_state(mint)?;
+    let authority = &state.authority;
+
+    println!("Mint:                 {mint}");
+    println!("Base coin:            {}", authority.base_coin);
+    println!("Admin:                {}", authority.admin);
+    println!("Fee collector:        {}", authority.fee_collector);
+    println!(
+        "Fee payout:           {}",
+        match fee_payout(authority) {
+            FeePayout::BaseCoin => "base coin",
+            FeePayout::Mint => "mint",
+        }
+    );
+    println!("Mutable:              {}", authority.mutable == 1);
+    println!("Gift card:            {}", authority.gift_card == 1);
+    println!("Blocklist:            {}", authority.blocklist == 1);
+    println!("Non-transferable:     {}", authority.non_transferable == 1);
+    println!("Mint to base ratio:   {}", authority.mint_to_base_ratio);
+    println!(
+        "Issuance fee:         {} bps",
+        authority.issuance_fee_basis_pts
+    );
+    println!(
+        "Redemption fee:       {} bps",
+        authority.redemption_fee_basis_pts
+    );
+    println!("Fees collected:       {}", authority.fees_collected);
+    println!("Protocol fees owed:   {}", authority.protocol_fees_accrued);
+    println!(
+        "Supply:               {} ({} decimals)",
+        state.supply, state.decimals
+    );
+    println!("Reserves:             {}", state.reserves);
+    if state.supply > 0 {
+        // 1.0 means every token is backed at the ratio it was first issued at
+        let backing =
+            state.reserves as f64 * authority.mint_to_base_ratio as f64 / state.supply as f64;
+        println!("Backing ratio:        {backing:.6}");
+    }
+
+    Ok(())
+}
+
+fn attestations(rpc: &Rpc, mint: &Pubkey) -> Result<()> {
+    let Some(attestation) = fetch_reserve_attestation(&rpc.client, mint)? else {
+        println!("No reserves attested yet");
+        return Ok(());
+    };
+
+    println!(
+        "{:>12}  {:>12}  {:>20}  {:>20}  {:>20}  {:>12}",
+        "Slot", "Timestamp", "Reserves", "Supply", "Withheld", "Backing"
+    );
+    for record in attestation.records() {
+        // an empty supply is reported as u64::MAX basis points
+        let backing = if record.supply == 0 {
+            "-".to_string()
+        } else {
+            format!("{:.4}", record.backing_ratio_basis_pts as f64 / 10_000.0)
+        };
+        println!(
+            "{:>12}  {:>12}  {:>20}  {:>20}  {:>20}  {:>12}",
+            record.slot, record.timestamp, record.reserves, record.supply, record.withheld, backing
+        );
+    }
+    println!("{} attestations made in total", attestation.count);
+
+    Ok(())
+}
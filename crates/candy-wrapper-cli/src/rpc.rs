@@ -0,0 +1,80 @@
+use anchor_spl::token_2022::spl_token_2022::{extension::StateWithExtensions, state::Mint};
+use anyhow::Result;
+use candy_wrapper_client::{
+    pda,
+    rpc::{fetch_authority, fetch_token_program},
+    Authority,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+
+pub struct Rpc {
+    pub client: RpcClient,
+    pub payer: Keypair,
+}
+
+/// On-chain state of a mint and the reserves backing it.
+pub struct MintState {
+    pub authority: Authority,
+    pub token_program_base_coin: Pubkey,
+    pub supply: u64,
+    pub decimals: u8,
+    pub reserves: u64,
+}
+
+impl Rpc {
+    pub fn new(url: String, payer: Keypair) -> Self {
+        Self {
+            client: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+            payer,
+        }
+    }
+
+    pub fn send(
+        &self,
+        instructions: &[Instruction],
+        extra_signers: &[&Keypair],
+    ) -> Result<Signature> {
+        let mut signers = vec![&self.payer];
+        signers.extend_from_slice(extra_signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &signers,
+            self.client.get_latest_blockhash()?,
+        );
+        Ok(self.client.send_and_confirm_transaction(&transaction)?)
+    }
+
+    pub fn mint_state(&self, mint: &Pubkey) -> Result<MintState> {
+        let authority = fetch_authority(&self.client, mint)?;
+        let token_program_base_coin = fetch_token_program(&self.client, &authority.base_coin)?;
+
+        let mint_data = self.client.get_account_data(mint)?;
+        let mint_account = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+        let reserves = self.client.get_token_account_balance(&pda::reserves(
+            mint,
+            &authority.base_coin,
+            &token_program_base_coin,
+        ));
+
+        Ok(MintState {
+            authority,
+            token_program_base_coin,
+            supply: mint_account.base.supply,
+            decimals: mint_account.base.decimals,
+            // the reserves account is only created on the first issuance
+            reserves: match reserves {
+                Ok(balance) => balance.amount.parse()?,
+                Err(_) => 0,
+            },
+        })
+    }
+}
@@ -0,0 +1,866 @@
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::{instruction::Instruction, system_program, sysvar},
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address_with_program_id},
+    token_2022,
+};
+use candy_wrapper::{accounts, instruction};
+
+use crate::{pda, CreateMintArgs, FeePayout, FeeSplitRecipient, ID, PROTOCOL_WALLET};
+
+/// Base coin and token program of a mint whose protocol fees are claimed by
+/// [`claim_protocol_fees`].
+pub struct ProtocolFeeClaim {
+    pub mint: Pubkey,
+    pub base_coin: Pubkey,
+    pub token_program_base_coin: Pubkey,
+}
+
+/// Program accounts of a mint closed by [`close_account`] that are passed only if they exist,
+/// see `rpc::fetch_closable_accounts`. The program requires each of them whenever the mint's
+/// `Authority` says it exists. Its reserve attestation and compliance accounts are always passed.
+#[derive(Default)]
+pub struct ClosableAccounts {
+    /// Wallets of the fee split, in the order they were set. What is left for the split is paid
+    /// out to them.
+    pub fee_split_recipients: Option<Vec<Pubkey>>,
+    pub merchant_registry: bool,
+    pub extra_account_meta_list: bool,
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn mint_token_account(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, &token_2022::ID)
+}
+
+pub fn set_protocol_fee(fee_basis_pts: u16) -> Instruction {
+    build(
+        accounts::SetProtocolFeeCtx {
+            payer: PROTOCOL_WALLET,
+            protocol_fee_config: pda::config().0,
+            system_program: system_program::ID,
+        },
+        instruction::SetProtocolFee { fee_basis_pts },
+    )
+}
+
+/// The accounts needed by base coin transfer hooks, see [`claim_protocol_fees_transfers`], are
+/// appended after those of every claim.
+pub fn claim_protocol_fees(payer: &Pubkey, claims: &[ProtocolFeeClaim]) -> Instruction {
+    let mut ix = build(
+        accounts::ClaimProtocolFeesCtx { payer: *payer },
+        instruction::ClaimProtocolFees {
+            mints: claims.len() as u8,
+        },
+    );
+    for claim in claims {
+        ix.accounts.extend([
+            AccountMeta::new(pda::authority(&claim.mint).0, false),
+            AccountMeta::new(pda::fee_vault(&claim.mint).0, false),
+            AccountMeta::new_readonly(claim.base_coin, false),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(
+                    &PROTOCOL_WALLET,
+                    &claim.base_coin,
+                    &claim.token_program_base_coin,
+                ),
+                false,
+            ),
+            AccountMeta::new_readonly(claim.token_program_base_coin, false),
+        ]);
+    }
+    ix
+}
+
+pub fn set_to_immutable(admin: &Pubkey, mint: &Pubkey) -> Instruction {
+    build(
+        accounts::ImmutableCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+        },
+        instruction::SetToImmutable {},
+    )
+}
+
+pub fn change_admin(admin: &Pubkey, mint: &Pubkey, new_admin: Pubkey) -> Instruction {
+    build(
+        accounts::AdminCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+        },
+        instruction::ChangeAdmin { new_admin },
+    )
+}
+
+/// The fee vault must be empty, so fees must be withdrawn and the protocol's share claimed
+/// first, see [`withdraw_fees`] and [`claim_protocol_fees`]. The token accounts of the fee split
+/// recipients, if any, are appended, ahead of the accounts needed by a base coin transfer hook.
+pub fn close_account(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+    fee_collector: &Pubkey,
+    closable: &ClosableAccounts,
+) -> Instruction {
+    let authority = pda::authority(mint).0;
+    let mut ix = build(
+        accounts::CloseAccountCtx {
+            payer: *admin,
+            authority,
+            mint: *mint,
+            base_coin: *base_coin,
+            authority_base_coin_token_account: pda::reserves(
+                mint,
+                base_coin,
+                token_program_base_coin,
+            ),
+            fee_vault_base_coin_token_account: pda::fee_vault(mint).0,
+            fee_collector_base_coin_token_account: get_associated_token_address_with_program_id(
+                fee_collector,
+                base_coin,
+                token_program_base_coin,
+            ),
+            authority_mint_token_account: mint_token_account(&authority, mint),
+            token_program_mint: token_2022::ID,
+            token_program_base_coin: *token_program_base_coin,
+            fee_split: closable
+                .fee_split_recipients
+                .is_some()
+                .then(|| pda::fee_split(mint).0),
+            merchant_registry: closable
+                .merchant_registry
+                .then(|| pda::merchant_registry(mint).0),
+            extra_account_meta_list: closable
+                .extra_account_meta_list
+                .then(|| pda::extra_account_metas(mint).0),
+            reserve_attestation: pda::reserve_attestation(mint).0,
+            compliance: pda::compliance(mint).0,
+        },
+        instruction::CloseAccount {},
+    );
+    for wallet in closable.fee_split_recipients.iter().flatten() {
+        ix.accounts.push(AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                wallet,
+                base_coin,
+                token_program_base_coin,
+            ),
+            false,
+        ));
+    }
+    ix
+}
+
+pub fn change_fee_collector(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    new_fee_collector: Pubkey,
+) -> Instruction {
+    build(
+        accounts::FeeCollectorCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+        },
+        instruction::ChangeFeeCollector { new_fee_collector },
+    )
+}
+
+pub fn change_fee_payout(admin: &Pubkey, mint: &Pubkey, fee_payout: FeePayout) -> Instruction {
+    build(
+        accounts::FeePayoutCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+        },
+        instruction::ChangeFeePayout { fee_payout },
+    )
+}
+
+/// `mint` is [`pda::mint`] of `payer` and `args.mint_seed` if set, otherwise a fresh keypair
+/// that must sign the transaction. The program allocates it.
+pub fn create_mint(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    token_program_base_coin: &Pubkey,
+    args: CreateMintArgs,
+) -> Instruction {
+    let gift_card = args.gift_card;
+    let program_hook = args.gift_card || args.blocklist;
+    let mint_signs = args.mint_seed.is_none();
+    let mut ix = build(
+        accounts::CreateMintCtx {
+            payer: *payer,
+            mint: *mint,
+            authority: pda::authority(mint).0,
+            base_coin: args.base_coin,
+            protocol_wallet: PROTOCOL_WALLET,
+            protocol_base_coin_token_account: get_associated_token_address_with_program_id(
+                &PROTOCOL_WALLET,
+                &args.base_coin,
+                token_program_base_coin,
+            ),
+            fee_vault_base_coin_token_account: pda::fee_vault(mint).0,
+            extra_account_meta_list: program_hook.then(|| pda::extra_account_metas(mint).0),
+            merchant_registry: gift_card.then(|| pda::merchant_registry(mint).0),
+            token_program_mint: token_2022::ID,
+            token_program_base_coin: *token_program_base_coin,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::CreateMint { args },
+    );
+    ix.accounts[1].is_signer = mint_signs;
+    ix
+}
+
+pub fn create_mint_metadata(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    lamports: u64,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Instruction {
+    build(
+        accounts::CreateMintMetadataCtx {
+            payer: *admin,
+            mint: *mint,
+            authority: pda::authority(mint).0,
+            token_program_mint: token_2022::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateMintMetadata {
+            lamports,
+            name,
+            symbol,
+            uri,
+        },
+    )
+}
+
+pub fn change_transfer_fee(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    fee_basis_pts: u16,
+    max_fee: u64,
+) -> Instruction {
+    build(
+        accounts::TransferFeeCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+            mint: *mint,
+            token_program_mint: token_2022::ID,
+        },
+        instruction::ChangeTransferFee {
+            fee_basis_pts,
+            max_fee,
+        },
+    )
+}
+
+/// Only succeeds if the mint's transfer hook was created with its `Authority` as authority.
+pub fn change_transfer_hook(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::TransferHookCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+            mint: *mint,
+            token_program_mint: token_2022::ID,
+        },
+        instruction::ChangeTransferHook { program_id },
+    )
+}
+
+pub fn issue_mint(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::IssueMintCtx {
+            payer: *payer,
+            payer_mint_token_account: mint_token_account(payer, mint),
+            payer_base_coin_token_account: get_associated_token_address_with_program_id(
+                payer,
+                base_coin,
+                token_program_base_coin,
+            ),
+            mint: *mint,
+            base_coin: *base_coin,
+            authority_base_coin_token_account: pda::reserves(
+                mint,
+                base_coin,
+                token_program_base_coin,
+            ),
+            authority: pda::authority(mint).0,
+            fee_vault_base_coin_token_account: pda::fee_vault(mint).0,
+            payer_blocklist_entry: pda::blocklist_entry(mint, payer).0,
+            token_program_mint: token_2022::ID,
+            token_program_base_coin: *token_program_base_coin,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::IssueMint { amount },
+    )
+}
+
+pub fn redeem_basecoin(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::RedeemBaseCoinCtx {
+            payer: *payer,
+            payer_mint_token_account: mint_token_account(payer, mint),
+            payer_base_coin_token_account: get_associated_token_address_with_program_id(
+                payer,
+                base_coin,
+                token_program_base_coin,
+            ),
+            mint: *mint,
+            base_coin: *base_coin,
+            authority_base_coin_token_account: pda::reserves(
+                mint,
+                base_coin,
+                token_program_base_coin,
+            ),
+            authority: pda::authority(mint).0,
+            fee_vault_base_coin_token_account: pda::fee_vault(mint).0,
+            payer_blocklist_entry: pda::blocklist_entry(mint, payer).0,
+            token_program_mint: token_2022::ID,
+            token_program_base_coin: *token_program_base_coin,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::RedeemBasecoin { amount },
+    )
+}
+
+fn withdraw_fees_accounts(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+    fee_collector: &Pubkey,
+    fee_payout: FeePayout,
+) -> accounts::WithdrawFeesCtx {
+    let authority = pda::authority(mint).0;
+    accounts::WithdrawFeesCtx {
+        payer: *payer,
+        mint: *mint,
+        base_coin: *base_coin,
+        authority,
+        protocol_fee_config: pda::config().0,
+        authority_mint_token_account: mint_token_account(&authority, mint),
+        authority_base_coin_token_account: pda::reserves(mint, base_coin, token_program_base_coin),
+        fee_vault_base_coin_token_account: pda::fee_vault(mint).0,
+        fee_collector_base_coin_token_account: get_associated_token_address_with_program_id(
+            fee_collector,
+            base_coin,
+            token_program_base_coin,
+        ),
+        fee_collector_mint_token_account: (fee_payout == FeePayout::Mint)
+            .then(|| mint_token_account(fee_collector, mint)),
+        token_program_mint: token_2022::ID,
+        token_program_base_coin: *token_program_base_coin,
+        associated_token_program: associated_token::ID,
+        system_program: system_program::ID,
+    }
+}
+
+/// `fee_collector` and `fee_payout` are read from the mint's `Authority`.
+pub fn withdraw_fees(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+    fee_collector: &Pubkey,
+    fee_payout: FeePayout,
+) -> Instruction {
+    build(
+        withdraw_fees_accounts(
+            payer,
+            mint,
+            base_coin,
+            token_program_base_coin,
+            fee_collector,
+            fee_payout,
+        ),
+        instruction::WithdrawFees {},
+    )
+}
+
+/// Like [`withdraw_fees`], first harvesting the fees withheld in `holder_token_accounts`.
+pub fn harvest_and_withdraw_fees(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+    fee_collector: &Pubkey,
+    fee_payout: FeePayout,
+    holder_token_accounts: &[Pubkey],
+) -> Instruction {
+    let mut ix = build(
+        withdraw_fees_accounts(
+            payer,
+            mint,
+            base_coin,
+            token_program_base_coin,
+            fee_collector,
+            fee_payout,
+        ),
+        instruction::HarvestAndWithdrawFees {},
+    );
+    ix.accounts.extend(
+        holder_token_accounts
+            .iter()
+            .map(|token_account| AccountMeta::new(*token_account, false)),
+    );
+    ix
+}
+
+/// Token transfer made by an instruction. If the token has a transfer hook, the accounts it
+/// needs for each transfer are appended to the instruction.
+pub struct TokenTransfer {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// Base coin transfers of [`issue_mint`].
+pub fn issue_mint_transfers(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+) -> Vec<TokenTransfer> {
+    let source =
+        get_associated_token_address_with_program_id(payer, base_coin, token_program_base_coin);
+    vec![
+        TokenTransfer {
+            source,
+            destination: pda::fee_vault(mint).0,
+            authority: *payer,
+        },
+        TokenTransfer {
+            source,
+            destination: pda::reserves(mint, base_coin, token_program_base_coin),
+            authority: *payer,
+        },
+    ]
+}
+
+/// Base coin transfers of [`redeem_basecoin`].
+pub fn redeem_basecoin_transfers(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+) -> Vec<TokenTransfer> {
+    let source = pda::reserves(mint, base_coin, token_program_base_coin);
+    let authority = pda::authority(mint).0;
+    vec![
+        TokenTransfer {
+            source,
+            destination: pda::fee_vault(mint).0,
+            authority,
+        },
+        TokenTransfer {
+            source,
+            destination: get_associated_token_address_with_program_id(
+                payer,
+                base_coin,
+                token_program_base_coin,
+            ),
+            authority,
+        },
+    ]
+}
+
+/// Base coin transfer of [`claim_protocol_fees`] for one claim.
+pub fn claim_protocol_fees_transfers(claim: &ProtocolFeeClaim) -> Vec<TokenTransfer> {
+    vec![TokenTransfer {
+        source: pda::fee_vault(&claim.mint).0,
+        destination: get_associated_token_address_with_program_id(
+            &PROTOCOL_WALLET,
+            &claim.base_coin,
+            &claim.token_program_base_coin,
+        ),
+        authority: pda::authority(&claim.mint).0,
+    }]
+}
+
+/// Base coin transfers of [`distribute`].
+pub fn distribute_transfers(
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+    recipients: &[Pubkey],
+) -> Vec<TokenTransfer> {
+    let fee_split = pda::fee_split(mint).0;
+    let source = get_associated_token_address_with_program_id(
+        &fee_split,
+        base_coin,
+        token_program_base_coin,
+    );
+    recipients
+        .iter()
+        .map(|wallet| TokenTransfer {
+            source,
+            destination: get_associated_token_address_with_program_id(
+                wallet,
+                base_coin,
+                token_program_base_coin,
+            ),
+            authority: fee_split,
+        })
+        .collect()
+}
+
+/// Base coin transfers of [`withdraw_fees`] and [`harvest_and_withdraw_fees`].
+pub fn withdraw_fees_transfers(
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+    fee_collector: &Pubkey,
+) -> Vec<TokenTransfer> {
+    let reserves = pda::reserves(mint, base_coin, token_program_base_coin);
+    let fee_vault = pda::fee_vault(mint).0;
+    let fee_collector = get_associated_token_address_with_program_id(
+        fee_collector,
+        base_coin,
+        token_program_base_coin,
+    );
+    let authority = pda::authority(mint).0;
+    [
+        (fee_vault, fee_collector),
+        (reserves, fee_collector),
+        (reserves, fee_vault),
+    ]
+    .into_iter()
+    .map(|(source, destination)| TokenTransfer {
+        source,
+        destination,
+        authority,
+    })
+    .collect()
+}
+
+/// Base coin transfers of [`close_account`], sweeping the dust left in the reserves and paying
+/// out what is left for a fee split.
+pub fn close_account_transfers(
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+    fee_collector: &Pubkey,
+    closable: &ClosableAccounts,
+) -> Vec<TokenTransfer> {
+    let mut transfers = vec![TokenTransfer {
+        source: pda::reserves(mint, base_coin, token_program_base_coin),
+        destination: get_associated_token_address_with_program_id(
+            fee_collector,
+            base_coin,
+            token_program_base_coin,
+        ),
+        authority: pda::authority(mint).0,
+    }];
+    if let Some(recipients) = &closable.fee_split_recipients {
+        transfers.extend(distribute_transfers(
+            mint,
+            base_coin,
+            token_program_base_coin,
+            recipients,
+        ));
+    }
+    transfers
+}
+
+/// Creator token transfers of [`withdraw_fees`] and [`harvest_and_withdraw_fees`] when fees are
+/// paid out in the mint.
+pub fn withdraw_fees_mint_transfers(mint: &Pubkey, fee_collector: &Pubkey) -> Vec<TokenTransfer> {
+    let authority = pda::authority(mint).0;
+    vec![TokenTransfer {
+        source: mint_token_account(&authority, mint),
+        destination: mint_token_account(fee_collector, mint),
+        authority,
+    }]
+}
+
+pub fn change_issuance_fee(admin: &Pubkey, mint: &Pubkey, fee_basis_pts: u16) -> Instruction {
+    build(
+        accounts::IssuanceFeeCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+        },
+        instruction::ChangeIssuanceFee { fee_basis_pts },
+    )
+}
+
+pub fn change_redemption_fee(admin: &Pubkey, mint: &Pubkey, fee_basis_pts: u16) -> Instruction {
+    build(
+        accounts::RedemptionFeeCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+        },
+        instruction::ChangeRedemptionFee { fee_basis_pts },
+    )
+}
+
+pub fn set_fee_split(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+    recipients: Vec<FeeSplitRecipient>,
+) -> Instruction {
+    let fee_split = pda::fee_split(mint).0;
+    build(
+        accounts::SetFeeSplitCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+            mint: *mint,
+            base_coin: *base_coin,
+            fee_split,
+            fee_split_base_coin_token_account: get_associated_token_address_with_program_id(
+                &fee_split,
+                base_coin,
+                token_program_base_coin,
+            ),
+            token_program_base_coin: *token_program_base_coin,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::SetFeeSplit { recipients },
+    )
+}
+
+/// `recipients` must be the wallets of the fee split, in the order they were set. The accounts
+/// needed by a base coin transfer hook, see [`distribute_transfers`], are appended after them.
+pub fn distribute(
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+    recipients: &[Pubkey],
+) -> Instruction {
+    let fee_split = pda::fee_split(mint).0;
+    let mut ix = build(
+        accounts::DistributeCtx {
+            fee_split,
+            base_coin: *base_coin,
+            fee_split_base_coin_token_account: get_associated_token_address_with_program_id(
+                &fee_split,
+                base_coin,
+                token_program_base_coin,
+            ),
+            token_program_base_coin: *token_program_base_coin,
+        },
+        instruction::Distribute {},
+    );
+    ix.accounts.extend(recipients.iter().map(|wallet| {
+        AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                wallet,
+                base_coin,
+                token_program_base_coin,
+            ),
+            false,
+        )
+    }));
+    ix
+}
+
+/// Pays out what is left in the fee split to `recipients`, given as for [`distribute`], closes
+/// the split and makes `new_fee_collector` the fee collector. Signed by the admin. Its transfers
+/// are those of [`distribute_transfers`].
+pub fn clear_fee_split(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+    recipients: &[Pubkey],
+    new_fee_collector: Pubkey,
+) -> Instruction {
+    let fee_split = pda::fee_split(mint).0;
+    let mut ix = build(
+        accounts::ClearFeeSplitCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+            mint: *mint,
+            base_coin: *base_coin,
+            fee_split,
+            fee_split_base_coin_token_account: get_associated_token_address_with_program_id(
+                &fee_split,
+                base_coin,
+                token_program_base_coin,
+            ),
+            token_program_base_coin: *token_program_base_coin,
+        },
+        instruction::ClearFeeSplit { new_fee_collector },
+    );
+    ix.accounts.extend(recipients.iter().map(|wallet| {
+        AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                wallet,
+                base_coin,
+                token_program_base_coin,
+            ),
+            false,
+        )
+    }));
+    ix
+}
+
+/// Grows the `Authority` account of a mint created before the fields after `admin` were added
+/// into the current layout, and creates its fee vault. Signed by the admin.
+pub fn migrate_authority(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::MigrateAuthorityCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+            mint: *mint,
+            base_coin: *base_coin,
+            fee_vault_base_coin_token_account: pda::fee_vault(mint).0,
+            token_program_base_coin: *token_program_base_coin,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateAuthority {},
+    )
+}
+
+pub fn attest_reserves(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    base_coin: &Pubkey,
+    token_program_base_coin: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::AttestReservesCtx {
+            payer: *payer,
+            mint: *mint,
+            base_coin: *base_coin,
+            authority: pda::authority(mint).0,
+            authority_base_coin_token_account: pda::reserves(
+                mint,
+                base_coin,
+                token_program_base_coin,
+            ),
+            reserve_attestation: pda::reserve_attestation(mint).0,
+            token_program_base_coin: *token_program_base_coin,
+            system_program: system_program::ID,
+        },
+        instruction::AttestReserves {},
+    )
+}
+
+pub fn add_merchant(admin: &Pubkey, mint: &Pubkey, wallet: Pubkey) -> Instruction {
+    build(
+        merchant_accounts(admin, mint),
+        instruction::AddMerchant { wallet },
+    )
+}
+
+pub fn remove_merchant(admin: &Pubkey, mint: &Pubkey, wallet: Pubkey) -> Instruction {
+    build(
+        merchant_accounts(admin, mint),
+        instruction::RemoveMerchant { wallet },
+    )
+}
+
+fn merchant_accounts(admin: &Pubkey, mint: &Pubkey) -> accounts::MerchantCtx {
+    accounts::MerchantCtx {
+        payer: *admin,
+        authority: pda::authority(mint).0,
+        mint: *mint,
+        merchant_registry: pda::merchant_registry(mint).0,
+    }
+}
+
+/// The new officer takes over blocking and unblocking wallets from any previous one.
+pub fn change_compliance_officer(admin: &Pubkey, mint: &Pubkey, officer: Pubkey) -> Instruction {
+    build(
+        accounts::ComplianceOfficerCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+            mint: *mint,
+            compliance: pda::compliance(mint).0,
+            system_program: system_program::ID,
+        },
+        instruction::ChangeComplianceOfficer { officer },
+    )
+}
+
+pub fn block_wallet(officer: &Pubkey, mint: &Pubkey, wallet: Pubkey) -> Instruction {
+    build(
+        accounts::BlockWalletCtx {
+            officer: *officer,
+            mint: *mint,
+            compliance: pda::compliance(mint).0,
+            blocklist_entry: pda::blocklist_entry(mint, &wallet).0,
+            system_program: system_program::ID,
+        },
+        instruction::BlockWallet { wallet },
+    )
+}
+
+pub fn unblock_wallet(officer: &Pubkey, mint: &Pubkey, wallet: Pubkey) -> Instruction {
+    build(
+        accounts::UnblockWalletCtx {
+            officer: *officer,
+            mint: *mint,
+            compliance: pda::compliance(mint).0,
+            blocklist_entry: pda::blocklist_entry(mint, &wallet).0,
+        },
+        instruction::UnblockWallet { wallet },
+    )
+}
+
+/// Fails unless the mint was created `freezable`.
+pub fn freeze_account(officer: &Pubkey, mint: &Pubkey, token_account: &Pubkey) -> Instruction {
+    build(
+        freeze_accounts(officer, mint, token_account),
+        instruction::FreezeAccount {},
+    )
+}
+
+pub fn thaw_account(officer: &Pubkey, mint: &Pubkey, token_account: &Pubkey) -> Instruction {
+    build(
+        freeze_accounts(officer, mint, token_account),
+        instruction::ThawAccount {},
+    )
+}
+
+fn freeze_accounts(officer: &Pubkey, mint: &Pubkey, token_account: &Pubkey) -> accounts::FreezeCtx {
+    accounts::FreezeCtx {
+        officer: *officer,
+        mint: *mint,
+        authority: pda::authority(mint).0,
+        compliance: pda::compliance(mint).0,
+        token_account: *token_account,
+        token_program_mint: token_2022::ID,
+    }
+}
@@ -0,0 +1,21 @@
+//! Rust client for the candy wrapper program.
+//!
+//! [`pda`] derives every program address, [`state`] decodes the program's accounts and
+//! [`instructions`] builds each instruction with its derived accounts and token programs
+//! already filled in. With the `rpc` feature, [`rpc`] fetches them from a cluster.
+
+pub mod instructions;
+pub mod pda;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+pub mod state;
+
+pub use candy_wrapper::{
+    instructions::{CreateMintArgs, TransferFeeArgs, TransferHookArgs},
+    state::{
+        AttestationRecord, Authority, BlocklistEntry, Compliance, FeePayout, FeeSplit,
+        FeeSplitRecipient, LegacyAuthority, MerchantRegistry, ProtocolFeeConfig,
+        ReserveAttestation, ATTESTATION_HISTORY_LEN, PROTOCOL_WALLET,
+    },
+    ID,
+};
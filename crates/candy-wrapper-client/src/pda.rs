@@ -0,0 +1,64 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+use crate::{ID, PROTOCOL_WALLET};
+
+/// Mint created by `creator` with `CreateMintArgs::mint_seed` set to `seed`.
+pub fn mint(creator: &Pubkey, seed: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"mint", creator.as_ref(), seed], &ID)
+}
+
+/// Authority of `mint`, which also owns the base coin reserves.
+pub fn authority(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"authority", mint.as_ref()], &ID)
+}
+
+/// Protocol fee config, shared by every mint.
+pub fn config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config", PROTOCOL_WALLET.as_ref()], &ID)
+}
+
+/// Base coin token account holding fees that have not been withdrawn yet.
+pub fn fee_vault(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"fee_vault", mint.as_ref()], &ID)
+}
+
+/// Fee split of `mint`, set as its fee collector by `set_fee_split`.
+pub fn fee_split(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"fee_split", mint.as_ref()], &ID)
+}
+
+/// History of attestations of the reserves backing `mint`, written by `attest_reserves`.
+pub fn reserve_attestation(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"reserve_attestation", mint.as_ref()], &ID)
+}
+
+/// Wallets that holders of gift card `mint` may transfer to, or receive from.
+pub fn merchant_registry(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"merchant_registry", mint.as_ref()], &ID)
+}
+
+/// Compliance officer of `mint`, set by `change_compliance_officer`.
+pub fn compliance(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"compliance", mint.as_ref()], &ID)
+}
+
+/// Exists while `wallet` is blocked from issuing, redeeming and transferring `mint`.
+pub fn blocklist_entry(mint: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"blocklist", mint.as_ref(), wallet.as_ref()], &ID)
+}
+
+/// Accounts this program needs to run as the transfer hook of `mint`, for gift cards and
+/// blocklists.
+pub fn extra_account_metas(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], &ID)
+}
+
+/// Base coin reserves backing `mint`.
+pub fn reserves(mint: &Pubkey, base_coin: &Pubkey, token_program_base_coin: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(
+        &authority(mint).0,
+        base_coin,
+        token_program_base_coin,
+    )
+}
@@ -0,0 +1,207 @@
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    Discriminator,
+};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    instruction::transfer_checked,
+    offchain::add_extra_account_metas,
+    state::{Account, Mint},
+};
+use anyhow::{anyhow, Context, Result};
+use candy_wrapper::state::AUTHORITY_SPACE;
+use futures::executor::block_on;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+
+use crate::{
+    instructions::{ClosableAccounts, TokenTransfer},
+    pda,
+    state::{decode_authority, decode_fee_split, decode_reserve_attestation},
+    Authority, ReserveAttestation, ID,
+};
+
+fn program_accounts_config(filters: Vec<RpcFilterType>) -> RpcProgramAccountsConfig {
+    RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    }
+}
+
+pub fn fetch_authority(client: &RpcClient, mint: &Pubkey) -> Result<Authority> {
+    let data = client
+        .get_account_data(&pda::authority(mint).0)
+        .with_context(|| format!("{mint} is not a candy wrapper mint"))?;
+    decode_authority(&data).map_err(|err| anyhow!("invalid authority account: {err}"))
+}
+
+/// Reserve attestations of `mint`, `None` until `attest_reserves` was first called for it.
+pub fn fetch_reserve_attestation(
+    client: &RpcClient,
+    mint: &Pubkey,
+) -> Result<Option<ReserveAttestation>> {
+    let Some(account) = client
+        .get_account_with_commitment(&pda::reserve_attestation(mint).0, client.commitment())?
+        .value
+    else {
+        return Ok(None);
+    };
+    decode_reserve_attestation(&account.data)
+        .map(Some)
+        .map_err(|err| anyhow!("invalid reserve attestation account: {err}"))
+}
+
+/// Program accounts of `mint` that `close_account` takes only if they exist.
+pub fn fetch_closable_accounts(client: &RpcClient, mint: &Pubkey) -> Result<ClosableAccounts> {
+    let accounts = client.get_multiple_accounts(&[
+        pda::fee_split(mint).0,
+        pda::merchant_registry(mint).0,
+        pda::extra_account_metas(mint).0,
+    ])?;
+    let fee_split_recipients = match &accounts[0] {
+        Some(account) => {
+            let fee_split = decode_fee_split(&account.data)
+                .map_err(|err| anyhow!("invalid fee split account: {err}"))?;
+            Some(
+                fee_split
+                    .recipients
+                    .iter()
+                    .map(|recipient| recipient.wallet)
+                    .collect(),
+            )
+        }
+        None => None,
+    };
+    Ok(ClosableAccounts {
+        fee_split_recipients,
+        merchant_registry: accounts[1].is_some(),
+        extra_account_meta_list: accounts[2].is_some(),
+    })
+}
+
+/// Every `Authority` account of the program, that is one per mint.
+pub fn fetch_authorities(client: &RpcClient) -> Result<Vec<(Pubkey, Authority)>> {
+    let accounts = client.get_program_accounts_with_config(
+        &ID,
+        program_accounts_config(vec![
+            RpcFilterType::DataSize(AUTHORITY_SPACE as u64),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &Authority::DISCRIMINATOR)),
+        ]),
+    )?;
+    accounts
+        .into_iter()
+        .map(|(address, account)| {
+            let authority = decode_authority(&account.data)
+                .map_err(|err| anyhow!("invalid authority account {address}: {err}"))?;
+            Ok((address, authority))
+        })
+        .collect()
+}
+
+/// Owner program of `mint`, either the token or the token 2022 program.
+pub fn fetch_token_program(client: &RpcClient, mint: &Pubkey) -> Result<Pubkey> {
+    Ok(client.get_account(mint)?.owner)
+}
+
+/// Transfer fees withdrawable from `mint` itself, that is already harvested from holders.
+/// Zero for non-transferable mints, which charge no transfer fees.
+pub fn fetch_mint_withheld_amount(client: &RpcClient, mint: &Pubkey) -> Result<u64> {
+    let data = client.get_account_data(mint)?;
+    let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+    Ok(mint
+        .get_extension::<TransferFeeConfig>()
+        .map_or(0, |config| config.withheld_amount.into()))
+}
+
+/// Token accounts of `mint` holding withheld transfer fees, with the amount they hold.
+pub fn fetch_withheld_token_accounts(
+    client: &RpcClient,
+    mint: &Pubkey,
+) -> Result<Vec<(Pubkey, u64)>> {
+    let accounts = client.get_program_accounts_with_config(
+        &spl_token_2022::ID,
+        program_accounts_config(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            mint.as_ref(),
+        ))]),
+    )?;
+
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(address, account)| {
+            let token_account = StateWithExtensions::<Account>::unpack(&account.data).ok()?;
+            let withheld: u64 = token_account
+                .get_extension::<TransferFeeAmount>()
+                .ok()?
+                .withheld_amount
+                .into();
+            (withheld > 0).then_some((address, withheld))
+        })
+        .collect())
+}
+
+/// Accounts the transfer hook of `mint` needs for `transfers`, to append to the instruction
+/// making them. Empty if the mint has no transfer hook. `amount` only matters to
+/// hooks whose accounts depend on it.
+pub fn fetch_transfer_hook_accounts(
+    client: &RpcClient,
+    mint: &Pubkey,
+    transfers: &[TokenTransfer],
+    amount: u64,
+) -> Result<Vec<AccountMeta>> {
+    let fetch = |address: Pubkey| {
+        let data = client
+            .get_account_with_commitment(&address, client.commitment())
+            .map(|response| response.value.map(|account| account.data))
+            .map_err(Into::into);
+        std::future::ready(data)
+    };
+
+    let mut accounts: Vec<AccountMeta> = vec![];
+    for transfer in transfers {
+        let mut ix = transfer_checked(
+            &spl_token_2022::ID,
+            &transfer.source,
+            mint,
+            &transfer.destination,
+            &transfer.authority,
+            &[],
+            amount,
+            0,
+        )?;
+        block_on(add_extra_account_metas(
+            &mut ix,
+            &transfer.source,
+            mint,
+            &transfer.destination,
+            &transfer.authority,
+            amount,
+            fetch,
+        ))
+        .map_err(|err| anyhow!("failed to resolve transfer hook accounts: {err}"))?;
+
+        // past the source, mint, destination and authority of the transfer
+        for meta in ix.accounts.into_iter().skip(4) {
+            match accounts
+                .iter_mut()
+                .find(|account| account.pubkey == meta.pubkey)
+            {
+                Some(account) => account.is_writable |= meta.is_writable,
+                None => accounts.push(meta),
+            }
+        }
+    }
+    Ok(accounts)
+}
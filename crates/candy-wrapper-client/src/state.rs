@@ -0,0 +1,75 @@
+use anchor_lang::{error::ErrorCode, AccountDeserialize, Discriminator, Result};
+
+use crate::{
+    Authority, Compliance, FeePayout, FeeSplit, LegacyAuthority, MerchantRegistry,
+    ProtocolFeeConfig, ReserveAttestation,
+};
+
+fn decode_zero_copy<T: Discriminator + bytemuck::Pod>(data: &[u8]) -> Result<T> {
+    if data.len() < T::DISCRIMINATOR.len() {
+        return Err(ErrorCode::AccountDiscriminatorNotFound.into());
+    }
+    let (discriminator, data) = data.split_at(T::DISCRIMINATOR.len());
+    if discriminator != T::DISCRIMINATOR {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+    let data = data
+        .get(..std::mem::size_of::<T>())
+        .ok_or(ErrorCode::AccountDidNotDeserialize)?;
+    Ok(bytemuck::pod_read_unaligned(data))
+}
+
+/// Decodes the data of an `Authority` account.
+pub fn decode_authority(data: &[u8]) -> Result<Authority> {
+    decode_zero_copy(data)
+}
+
+/// Decodes the data of an `Authority` account still in the legacy layout, see
+/// `instructions::migrate_authority`.
+pub fn decode_legacy_authority(data: &[u8]) -> Result<LegacyAuthority> {
+    if data.len() < Authority::DISCRIMINATOR.len() {
+        return Err(ErrorCode::AccountDiscriminatorNotFound.into());
+    }
+    let (discriminator, data) = data.split_at(Authority::DISCRIMINATOR.len());
+    if discriminator != Authority::DISCRIMINATOR {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+    if data.len() != std::mem::size_of::<LegacyAuthority>() {
+        return Err(ErrorCode::AccountDidNotDeserialize.into());
+    }
+    Ok(bytemuck::pod_read_unaligned(data))
+}
+
+/// Decodes the data of a `ReserveAttestation` account.
+pub fn decode_reserve_attestation(data: &[u8]) -> Result<ReserveAttestation> {
+    decode_zero_copy(data)
+}
+
+/// Decodes the data of the `ProtocolFeeConfig` account.
+pub fn decode_protocol_fee_config(data: &[u8]) -> Result<ProtocolFeeConfig> {
+    ProtocolFeeConfig::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the data of a `FeeSplit` account.
+pub fn decode_fee_split(data: &[u8]) -> Result<FeeSplit> {
+    FeeSplit::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the data of a `MerchantRegistry` account.
+pub fn decode_merchant_registry(data: &[u8]) -> Result<MerchantRegistry> {
+    MerchantRegistry::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the data of a `Compliance` account.
+pub fn decode_compliance(data: &[u8]) -> Result<Compliance> {
+    Compliance::try_deserialize(&mut &data[..])
+}
+
+/// How the creator's share of transfer fees is paid out, stored as a `u8` in `Authority`.
+pub fn fee_payout(authority: &Authority) -> FeePayout {
+    if authority.fee_payout == FeePayout::Mint as u8 {
+        FeePayout::Mint
+    } else {
+        FeePayout::BaseCoin
+    }
+}
@@ -0,0 +1,148 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::decode::Event;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL,
+    block_time INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS events (
+    signature TEXT NOT NULL REFERENCES transactions (signature),
+    event_index INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    signer TEXT NOT NULL,
+    authority TEXT,
+    base_coin_amount INTEGER,
+    mint_amount INTEGER,
+    fee_amount INTEGER,
+    recipient TEXT,
+    details TEXT,
+    PRIMARY KEY (signature, event_index)
+);
+
+-- admin instructions only reference the authority, so mints are resolved through it
+CREATE TABLE IF NOT EXISTS mints (
+    authority TEXT PRIMARY KEY,
+    mint TEXT NOT NULL
+);
+
+CREATE VIEW IF NOT EXISTS mint_events AS
+SELECT events.*, mints.mint, transactions.slot, transactions.block_time,
+       date(transactions.block_time, 'unixepoch') AS day
+FROM events
+JOIN transactions USING (signature)
+LEFT JOIN mints USING (authority);
+
+CREATE VIEW IF NOT EXISTS mint_totals AS
+SELECT mint,
+       SUM(CASE WHEN kind = 'issue' THEN 1 ELSE 0 END) AS issuances,
+       SUM(CASE WHEN kind = 'issue' THEN base_coin_amount ELSE 0 END) AS issued_base_coin,
+       SUM(CASE WHEN kind = 'issue' THEN mint_amount ELSE 0 END) AS issued_mint,
+       SUM(CASE WHEN kind = 'issue' THEN fee_amount ELSE 0 END) AS issuance_fees,
+       SUM(CASE WHEN kind = 'redeem' THEN 1 ELSE 0 END) AS redemptions,
+       SUM(CASE WHEN kind = 'redeem' THEN mint_amount ELSE 0 END) AS redeemed_mint,
+       SUM(CASE WHEN kind = 'redeem' THEN base_coin_amount ELSE 0 END) AS redeemed_base_coin,
+       SUM(CASE WHEN kind = 'redeem' THEN fee_amount ELSE 0 END) AS redemption_fees,
+       SUM(CASE WHEN kind IN ('withdraw_fees', 'harvest_and_withdraw_fees')
+           THEN base_coin_amount ELSE 0 END) AS creator_fees_base_coin,
+       SUM(CASE WHEN kind IN ('withdraw_fees', 'harvest_and_withdraw_fees')
+           THEN mint_amount ELSE 0 END) AS creator_fees_mint
+FROM mint_events
+WHERE mint IS NOT NULL
+GROUP BY mint;
+
+CREATE VIEW IF NOT EXISTS mint_daily_totals AS
+SELECT mint, day,
+       SUM(CASE WHEN kind = 'issue' THEN 1 ELSE 0 END) AS issuances,
+       SUM(CASE WHEN kind = 'issue' THEN base_coin_amount ELSE 0 END) AS issued_base_coin,
+       SUM(CASE WHEN kind = 'issue' THEN fee_amount ELSE 0 END) AS issuance_fees,
+       SUM(CASE WHEN kind = 'redeem' THEN 1 ELSE 0 END) AS redemptions,
+       SUM(CASE WHEN kind = 'redeem' THEN base_coin_amount ELSE 0 END) AS redeemed_base_coin,
+       SUM(CASE WHEN kind = 'redeem' THEN fee_amount ELSE 0 END) AS redemption_fees,
+       SUM(CASE WHEN kind IN ('withdraw_fees', 'harvest_and_withdraw_fees')
+           THEN base_coin_amount ELSE 0 END) AS creator_fees_base_coin
+FROM mint_events
+WHERE mint IS NOT NULL
+GROUP BY mint, day;
+
+CREATE VIEW IF NOT EXISTS creator_fees AS
+SELECT recipient AS fee_collector, mint,
+       SUM(base_coin_amount) AS fees_base_coin,
+       SUM(mint_amount) AS fees_mint,
+       COUNT(*) AS withdrawals
+FROM mint_events
+WHERE kind IN ('withdraw_fees', 'harvest_and_withdraw_fees') AND recipient IS NOT NULL
+GROUP BY recipient, mint;
+";
+
+pub struct Db {
+    conn: Connection,
+}
+
+impl Db {
+    pub fn open(path: &str) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Most recent transaction indexed, where the next run resumes from.
+    pub fn latest_signature(&self) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT signature FROM transactions ORDER BY slot DESC, rowid DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Stores a transaction and its events atomically, so a crash never leaves it half
+    /// indexed.
+    pub fn insert_transaction(
+        &mut self,
+        signature: &str,
+        slot: u64,
+        block_time: Option<i64>,
+        events: &[Event],
+    ) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT OR IGNORE INTO transactions (signature, slot, block_time) VALUES (?1, ?2, ?3)",
+            params![signature, slot, block_time],
+        )?;
+        for (event_index, event) in events.iter().enumerate() {
+            tx.execute(
+                "INSERT OR REPLACE INTO events (signature, event_index, kind, signer, authority,
+                     base_coin_amount, mint_amount, fee_amount, recipient, details)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    signature,
+                    event_index,
+                    event.kind,
+                    event.signer.to_string(),
+                    event.authority.as_ref().map(Pubkey::to_string),
+                    event.base_coin_amount.map(|amount| amount as i64),
+                    event.mint_amount.map(|amount| amount as i64),
+                    event.fee_amount.map(|amount| amount as i64),
+                    event.recipient.as_ref().map(Pubkey::to_string),
+                    event.details,
+                ],
+            )?;
+            if let (Some(authority), Some(mint)) = (event.authority, event.mint) {
+                tx.execute(
+                    "INSERT OR IGNORE INTO mints (authority, mint) VALUES (?1, ?2)",
+                    params![authority.to_string(), mint.to_string()],
+                )?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,673 @@
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use candy_wrapper::{
+    instruction as ix, instructions::protocol::claim_protocol_fees::CLAIM_ACCOUNTS_PER_MINT,
+};
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::UiTransactionTokenBalance;
+
+/// One decoded program instruction and the token movements it caused.
+#[derive(Debug, Default)]
+pub struct Event {
+    pub kind: &'static str,
+    pub signer: Pubkey,
+    pub authority: Option<Pubkey>,
+    pub mint: Option<Pubkey>,
+    /// Base coin deposited, released or paid out.
+    pub base_coin_amount: Option<u64>,
+    /// Creator tokens issued, burned or paid out.
+    pub mint_amount: Option<u64>,
+    /// Issuance or redemption fee moved into the fee vault.
+    pub fee_amount: Option<u64>,
+    /// Wallet receiving the payout, for fee withdrawals, distributions and claims.
+    pub recipient: Option<Pubkey>,
+    pub details: Option<String>,
+}
+
+/// Account keys and token balances of a transaction, shared by all of its instructions.
+pub struct TransactionContext {
+    pub account_keys: Vec<Pubkey>,
+    pub pre_token_balances: Vec<UiTransactionTokenBalance>,
+    pub post_token_balances: Vec<UiTransactionTokenBalance>,
+}
+
+impl TransactionContext {
+    fn token_balance(balances: &[UiTransactionTokenBalance], account_index: u8) -> Option<u64> {
+        balances
+            .iter()
+            .find(|balance| balance.account_index == account_index)
+            .and_then(|balance| balance.ui_token_amount.amount.parse().ok())
+    }
+
+    // balances are per transaction, so instructions touching the same token account in one
+    // transaction are attributed the combined movement
+    fn token_delta(&self, account_index: u8) -> i128 {
+        let pre = Self::token_balance(&self.pre_token_balances, account_index).unwrap_or(0);
+        let post = Self::token_balance(&self.post_token_balances, account_index).unwrap_or(0);
+        post as i128 - pre as i128
+    }
+
+    fn token_owner(&self, account_index: u8) -> Option<Pubkey> {
+        let balance = self
+            .post_token_balances
+            .iter()
+            .find(|balance| balance.account_index == account_index)?;
+        Option::<String>::from(balance.owner.clone())?.parse().ok()
+    }
+}
+
+/// Accounts of one instruction, resolved against its transaction.
+struct Accounts<'a> {
+    tx: &'a TransactionContext,
+    indexes: &'a [u8],
+}
+
+impl Accounts<'_> {
+    fn key(&self, position: usize) -> Option<Pubkey> {
+        let index = *self.indexes.get(position)?;
+        self.tx.account_keys.get(index as usize).copied()
+    }
+
+    fn received(&self, position: usize) -> Option<u64> {
+        let index = *self.indexes.get(position)?;
+        u64::try_from(self.tx.token_delta(index)).ok()
+    }
+
+    fn sent(&self, position: usize) -> Option<u64> {
+        let index = *self.indexes.get(position)?;
+        u64::try_from(-self.tx.token_delta(index)).ok()
+    }
+
+    fn owner(&self, position: usize) -> Option<Pubkey> {
+        self.tx.token_owner(*self.indexes.get(position)?)
+    }
+}
+
+fn args<T: AnchorDeserialize>(data: &[u8]) -> Option<T> {
+    T::deserialize(&mut &data[8..]).ok()
+}
+
+/// Decodes a candy wrapper instruction into the events it produced, one per claimed mint for
+/// `claim_protocol_fees` and one otherwise. Unknown instructions produce none.
+pub fn decode_instruction(tx: &TransactionContext, indexes: &[u8], data: &[u8]) -> Vec<Event> {
+    if data.len() < 8 {
+        return vec![];
+    }
+    let accounts = Accounts { tx, indexes };
+    let discriminator = &data[..8];
+    let signer = accounts.key(0).unwrap_or_default();
+
+    // instructions only signed by the admin, with the authority as second account
+    let admin_event = |kind, details: Option<String>| Event {
+        kind,
+        signer,
+        authority: accounts.key(1),
+        details,
+        ..Event::default()
+    };
+
+    let event = if discriminator == ix::CreateMint::DISCRIMINATOR {
+        let Some(ix::CreateMint { args }) = args(data) else {
+            return vec![];
+        };
+        Event {
+            kind: "create_mint",
+            signer,
+            authority: accounts.key(2),
+            mint: accounts.key(1),
+            details: Some(format!(
+                "base_coin={} mint_to_base_ratio={} issuance_fee_basis_pts={} redemption_fee_basis_pts={} fee_collector={} transfer_fee_basis_pts={} max_transfer_fee={}{}{}{}{}{}{}",
+                args.base_coin,
+                args.mint_to_base_ratio,
+                args.issuance_fee_basis_pts,
+                args.redemption_fee_basis_pts,
+                args.fee_collector,
+                args.transfer_fee_args.fee_basis_pts,
+                args.transfer_fee_args.max_fee,
+                args.transfer_hook_args
+                    .map(|hook| format!(" transfer_hook_program={}", hook.program_id))
+                    .unwrap_or_default(),
+                if args.gift_card { " gift_card" } else { "" },
+                if args.blocklist { " blocklist" } else { "" },
+                if args.freezable { " freezable" } else { "" },
+                if args.default_frozen { " default_frozen" } else { "" },
+                if args.non_transferable { " non_transferable" } else { "" },
+            )),
+            ..Event::default()
+        }
+    } else if discriminator == ix::CreateMintMetadata::DISCRIMINATOR {
+        let Some(ix::CreateMintMetadata {
+            name, symbol, uri, ..
+        }) = args(data)
+        else {
+            return vec![];
+        };
+        Event {
+            kind: "create_mint_metadata",
+            signer,
+            authority: accounts.key(2),
+            mint: accounts.key(1),
+            details: Some(format!("name={name} symbol={symbol} uri={uri}")),
+            ..Event::default()
+        }
+    } else if discriminator == ix::IssueMint::DISCRIMINATOR {
+        let Some(ix::IssueMint { amount }) = args(data) else {
+            return vec![];
+        };
+        Event {
+            kind: "issue",
+            signer,
+            authority: accounts.key(6),
+            mint: accounts.key(3),
+            base_coin_amount: Some(amount),
+            mint_amount: accounts.received(1),
+            fee_amount: accounts.received(7),
+            ..Event::default()
+        }
+    } else if discriminator == ix::RedeemBasecoin::DISCRIMINATOR {
+        let Some(ix::RedeemBasecoin { amount }) = args(data) else {
+            return vec![];
+        };
+        Event {
+            kind: "redeem",
+            signer,
+            authority: accounts.key(6),
+            mint: accounts.key(3),
+            base_coin_amount: accounts.received(2),
+            mint_amount: Some(amount),
+            fee_amount: accounts.received(7),
+            ..Event::default()
+        }
+    } else if discriminator == ix::WithdrawFees::DISCRIMINATOR
+        || discriminator == ix::HarvestAndWithdrawFees::DISCRIMINATOR
+    {
+        // without a fee collector mint token account anchor passes the program id instead
+        let paid_in_mint = accounts
+            .key(9)
+            .filter(|key| *key != candy_wrapper::ID)
+            .and_then(|_| accounts.received(9));
+        Event {
+            kind: if discriminator == ix::WithdrawFees::DISCRIMINATOR {
+                "withdraw_fees"
+            } else {
+                "harvest_and_withdraw_fees"
+            },
+            signer,
+            authority: accounts.key(3),
+            mint: accounts.key(1),
+            base_coin_amount: accounts.received(8),
+            mint_amount: paid_in_mint,
+            recipient: accounts.owner(8),
+            ..Event::default()
+        }
+    } else if discriminator == ix::ChangeAdmin::DISCRIMINATOR {
+        let Some(ix::ChangeAdmin { new_admin }) = args(data) else {
+            return vec![];
+        };
+        admin_event("change_admin", Some(format!("new_admin={new_admin}")))
+    } else if discriminator == ix::ChangeFeeCollector::DISCRIMINATOR {
+        let Some(ix::ChangeFeeCollector { new_fee_collector }) = args(data) else {
+            return vec![];
+        };
+        admin_event(
+            "change_fee_collector",
+            Some(format!("new_fee_collector={new_fee_collector}")),
+        )
+    } else if discriminator == ix::ChangeFeePayout::DISCRIMINATOR {
+        let Some(ix::ChangeFeePayout { fee_payout }) = args(data) else {
+            return vec![];
+        };
+        admin_event(
+            "change_fee_payout",
+            Some(format!("fee_payout={}", fee_payout as u8)),
+        )
+    } else if discriminator == ix::ChangeIssuanceFee::DISCRIMINATOR {
+        let Some(ix::ChangeIssuanceFee { fee_basis_pts }) = args(data) else {
+            return vec![];
+        };
+        admin_event(
+            "change_issuance_fee",
+            Some(format!("fee_basis_pts={fee_basis_pts}")),
+        )
+    } else if discriminator == ix::ChangeRedemptionFee::DISCRIMINATOR {
+        let Some(ix::ChangeRedemptionFee { fee_basis_pts }) = args(data) else {
+            return vec![];
+        };
+        admin_event(
+            "change_redemption_fee",
+            Some(format!("fee_basis_pts={fee_basis_pts}")),
+        )
+    } else if discriminator == ix::ChangeTransferFee::DISCRIMINATOR {
+        let Some(ix::ChangeTransferFee {
+            fee_basis_pts,
+            max_fee,
+        }) = args(data)
+        else {
+            return vec![];
+        };
+        Event {
+            mint: accounts.key(2),
+            ..admin_event(
+                "change_transfer_fee",
+                Some(format!("fee_basis_pts={fee_basis_pts} max_fee={max_fee}")),
+            )
+        }
+    } else if discriminator == ix::ChangeTransferHook::DISCRIMINATOR {
+        let Some(ix::ChangeTransferHook { program_id }) = args(data) else {
+            return vec![];
+        };
+        Event {
+            mint: accounts.key(2),
+            ..admin_event(
+                "change_transfer_hook",
+                Some(match program_id {
+                    Some(program_id) => format!("program_id={program_id}"),
+                    None => "program_id=none".to_string(),
+                }),
+            )
+        }
+    } else if discriminator == ix::AddMerchant::DISCRIMINATOR {
+        let Some(ix::AddMerchant { wallet }) = args(data) else {
+            return vec![];
+        };
+        Event {
+            mint: accounts.key(2),
+            ..admin_event("add_merchant", Some(format!("wallet={wallet}")))
+        }
+    } else if discriminator == ix::RemoveMerchant::DISCRIMINATOR {
+        let Some(ix::RemoveMerchant { wallet }) = args(data) else {
+            return vec![];
+        };
+        Event {
+            mint: accounts.key(2),
+            ..admin_event("remove_merchant", Some(format!("wallet={wallet}")))
+        }
+    } else if discriminator == ix::ChangeComplianceOfficer::DISCRIMINATOR {
+        let Some(ix::ChangeComplianceOfficer { officer }) = args(data) else {
+            return vec![];
+        };
+        Event {
+            mint: accounts.key(2),
+            ..admin_event(
+                "change_compliance_officer",
+                Some(format!("officer={officer}")),
+            )
+        }
+    } else if discriminator == ix::BlockWallet::DISCRIMINATOR {
+        let Some(ix::BlockWallet { wallet }) = args(data) else {
+            return vec![];
+        };
+        Event {
+            kind: "block_wallet",
+            signer,
+            mint: accounts.key(1),
+            details: Some(format!("wallet={wallet}")),
+            ..Event::default()
+        }
+    } else if discriminator == ix::UnblockWallet::DISCRIMINATOR {
+        let Some(ix::UnblockWallet { wallet }) = args(data) else {
+            return vec![];
+        };
+        Event {
+            kind: "unblock_wallet",
+            signer,
+            mint: accounts.key(1),
+            details: Some(format!("wallet={wallet}")),
+            ..Event::default()
+        }
+    } else if discriminator == ix::FreezeAccount::DISCRIMINATOR
+        || discriminator == ix::ThawAccount::DISCRIMINATOR
+    {
+        Event {
+            kind: if discriminator == ix::FreezeAccount::DISCRIMINATOR {
+                "freeze_account"
+            } else {
+                "thaw_account"
+            },
+            signer,
+            authority: accounts.key(2),
+            mint: accounts.key(1),
+            details: accounts
+                .key(4)
+                .map(|token_account| format!("token_account={token_account}")),
+            ..Event::default()
+        }
+    } else if discriminator == ix::SetToImmutable::DISCRIMINATOR {
+        admin_event("set_to_immutable", None)
+    } else if discriminator == ix::CloseAccount::DISCRIMINATOR {
+        Event {
+            mint: accounts.key(2),
+            ..admin_event("close_account", None)
+        }
+    } else if discriminator == ix::SetFeeSplit::DISCRIMINATOR {
+        let Some(ix::SetFeeSplit { recipients }) = args(data) else {
+            return vec![];
+        };
+        let recipients: Vec<String> = recipients
+            .iter()
+            .map(|recipient| format!("{}:{}", recipient.wallet, recipient.basis_pts))
+            .collect();
+        Event {
+            mint: accounts.key(2),
+            ..admin_event(
+                "set_fee_split",
+                Some(format!("recipients={}", recipients.join(","))),
+            )
+        }
+    } else if discriminator == ix::ClearFeeSplit::DISCRIMINATOR {
+        let Some(ix::ClearFeeSplit { new_fee_collector }) = args(data) else {
+            return vec![];
+        };
+        Event {
+            mint: accounts.key(2),
+            base_coin_amount: accounts.sent(5),
+            ..admin_event(
+                "clear_fee_split",
+                Some(format!("new_fee_collector={new_fee_collector}")),
+            )
+        }
+    } else if discriminator == ix::Distribute::DISCRIMINATOR {
+        Event {
+            kind: "distribute",
+            signer,
+            base_coin_amount: accounts.sent(2),
+            details: accounts
+                .key(0)
+                .map(|fee_split| format!("fee_split={fee_split}")),
+            ..Event::default()
+        }
+    } else if discriminator == ix::AttestReserves::DISCRIMINATOR {
+        Event {
+            kind: "attest_reserves",
+            signer,
+            authority: accounts.key(3),
+            mint: accounts.key(1),
+            ..Event::default()
+        }
+    } else if discriminator == ix::SetProtocolFee::DISCRIMINATOR {
+        let Some(ix::SetProtocolFee { fee_basis_pts }) = args(data) else {
+            return vec![];
+        };
+        Event {
+            kind: "set_protocol_fee",
+            signer,
+            details: Some(format!("fee_basis_pts={fee_basis_pts}")),
+            ..Event::default()
+        }
+    } else if discriminator == ix::ClaimProtocolFees::DISCRIMINATOR {
+        let Some(ix::ClaimProtocolFees { mints }) = args(data) else {
+            return vec![];
+        };
+        return (0..usize::from(mints))
+            .map(|claim| 1 + claim * CLAIM_ACCOUNTS_PER_MINT)
+            .map(|group| Event {
+                kind: "claim_protocol_fees",
+                signer,
+                authority: accounts.key(group),
+                base_coin_amount: accounts.received(group + 3),
+                recipient: accounts.owner(group + 3),
+                ..Event::default()
+            })
+            .collect();
+    } else {
+        return vec![];
+    };
+
+    vec![event]
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_spl::{
+        associated_token::get_associated_token_address_with_program_id, token, token_2022,
+    };
+    use candy_wrapper_client::{
+        instructions::{self, ProtocolFeeClaim},
+        pda, FeePayout, PROTOCOL_WALLET,
+    };
+    use solana_account_decoder::parse_token::UiTokenAmount;
+    use solana_sdk::instruction::Instruction;
+    use solana_transaction_status::option_serializer::OptionSerializer;
+
+    use super::*;
+
+    /// A token account of the transaction, its owner and its balance before and after.
+    struct Balance {
+        account: Pubkey,
+        owner: Pubkey,
+        pre: u64,
+        post: u64,
+    }
+
+    fn token_balance(account_index: u8, owner: &Pubkey, amount: u64) -> UiTransactionTokenBalance {
+        UiTransactionTokenBalance {
+            account_index,
+            mint: Pubkey::default().to_string(),
+            ui_token_amount: UiTokenAmount {
+                ui_amount: None,
+                decimals: 0,
+                amount: amount.to_string(),
+                ui_amount_string: amount.to_string(),
+            },
+            owner: OptionSerializer::Some(owner.to_string()),
+            program_id: OptionSerializer::None,
+        }
+    }
+
+    // compiles `ix` into a transaction on its own, as the indexer sees it, so the decoder only
+    // finds its accounts by position
+    fn decode(ix: &Instruction, balances: &[Balance]) -> Vec<Event> {
+        let mut account_keys: Vec<Pubkey> = vec![];
+        let indexes: Vec<u8> = ix
+            .accounts
+            .iter()
+            .map(|meta| {
+                let index = account_keys
+                    .iter()
+                    .position(|key| *key == meta.pubkey)
+                    .unwrap_or_else(|| {
+                        account_keys.push(meta.pubkey);
+                        account_keys.len() - 1
+                    });
+                index as u8
+            })
+            .collect();
+        let index_of = |account: &Pubkey| {
+            account_keys
+                .iter()
+                .position(|key| key == account)
+                .expect("token account is not in the instruction") as u8
+        };
+        let tx = TransactionContext {
+            pre_token_balances: balances
+                .iter()
+                .map(|balance| {
+                    token_balance(index_of(&balance.account), &balance.owner, balance.pre)
+                })
+                .collect(),
+            post_token_balances: balances
+                .iter()
+                .map(|balance| {
+                    token_balance(index_of(&balance.account), &balance.owner, balance.post)
+                })
+                .collect(),
+            account_keys,
+        };
+        decode_instruction(&tx, &indexes, &ix.data)
+    }
+
+    fn decode_one(ix: &Instruction, balances: &[Balance]) -> Event {
+        let mut events = decode(ix, balances);
+        assert_eq!(events.len(), 1);
+        events.remove(0)
+    }
+
+    #[test]
+    fn decodes_issue() {
+        let payer = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let base_coin = Pubkey::new_unique();
+        let ix = instructions::issue_mint(&payer, &mint, &base_coin, &token::ID, 1_000);
+        let event = decode_one(
+            &ix,
+            &[
+                Balance {
+                    account: get_associated_token_address_with_program_id(
+                        &payer,
+                        &mint,
+                        &token_2022::ID,
+                    ),
+                    owner: payer,
+                    pre: 0,
+                    post: 990_000,
+                },
+                Balance {
+                    account: pda::fee_vault(&mint).0,
+                    owner: pda::authority(&mint).0,
+                    pre: 5,
+                    post: 15,
+                },
+            ],
+        );
+        assert_eq!(event.kind, "issue");
+        assert_eq!(event.signer, payer);
+        assert_eq!(event.authority, Some(pda::authority(&mint).0));
+        assert_eq!(event.mint, Some(mint));
+        assert_eq!(event.base_coin_amount, Some(1_000));
+        assert_eq!(event.mint_amount, Some(990_000));
+        assert_eq!(event.fee_amount, Some(10));
+    }
+
+    #[test]
+    fn decodes_redeem() {
+        let payer = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let base_coin = Pubkey::new_unique();
+        let ix = instructions::redeem_basecoin(&payer, &mint, &base_coin, &token::ID, 1_000_000);
+        let event = decode_one(
+            &ix,
+            &[
+                Balance {
+                    account: get_associated_token_address_with_program_id(
+                        &payer,
+                        &base_coin,
+                        &token::ID,
+                    ),
+                    owner: payer,
+                    pre: 100,
+                    post: 1_090,
+                },
+                Balance {
+                    account: pda::fee_vault(&mint).0,
+                    owner: pda::authority(&mint).0,
+                    pre: 0,
+                    post: 10,
+                },
+            ],
+        );
+        assert_eq!(event.kind, "redeem");
+        assert_eq!(event.signer, payer);
+        assert_eq!(event.authority, Some(pda::authority(&mint).0));
+        assert_eq!(event.mint, Some(mint));
+        assert_eq!(event.base_coin_amount, Some(990));
+        assert_eq!(event.mint_amount, Some(1_000_000));
+        assert_eq!(event.fee_amount, Some(10));
+    }
+
+    #[test]
+    fn decodes_withdraw_fees_in_base_coin_and_mint() {
+        let payer = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let base_coin = Pubkey::new_unique();
+        let fee_collector = Pubkey::new_unique();
+        let base_coin_balance = Balance {
+            account: get_associated_token_address_with_program_id(
+                &fee_collector,
+                &base_coin,
+                &token::ID,
+            ),
+            owner: fee_collector,
+            pre: 0,
+            post: 900,
+        };
+
+        let ix = instructions::withdraw_fees(
+            &payer,
+            &mint,
+            &base_coin,
+            &token::ID,
+            &fee_collector,
+            FeePayout::BaseCoin,
+        );
+        let event = decode_one(&ix, &[base_coin_balance]);
+        assert_eq!(event.kind, "withdraw_fees");
+        assert_eq!(event.signer, payer);
+        assert_eq!(event.authority, Some(pda::authority(&mint).0));
+        assert_eq!(event.mint, Some(mint));
+        assert_eq!(event.base_coin_amount, Some(900));
+        assert_eq!(event.mint_amount, None);
+        assert_eq!(event.recipient, Some(fee_collector));
+
+        let ix = instructions::harvest_and_withdraw_fees(
+            &payer,
+            &mint,
+            &base_coin,
+            &token::ID,
+            &fee_collector,
+            FeePayout::Mint,
+            &[],
+        );
+        let event = decode_one(
+            &ix,
+            &[Balance {
+                account: get_associated_token_address_with_program_id(
+                    &fee_collector,
+                    &mint,
+                    &token_2022::ID,
+                ),
+                owner: fee_collector,
+                pre: 0,
+                post: 50_000,
+            }],
+        );
+        assert_eq!(event.kind, "harvest_and_withdraw_fees");
+        assert_eq!(event.mint, Some(mint));
+        assert_eq!(event.mint_amount, Some(50_000));
+    }
+
+    #[test]
+    fn decodes_claim_protocol_fees_per_mint() {
+        let payer = Pubkey::new_unique();
+        let claims: Vec<ProtocolFeeClaim> = [token::ID, token_2022::ID]
+            .into_iter()
+            .map(|token_program_base_coin| ProtocolFeeClaim {
+                mint: Pubkey::new_unique(),
+                base_coin: Pubkey::new_unique(),
+                token_program_base_coin,
+            })
+            .collect();
+        let ix = instructions::claim_protocol_fees(&payer, &claims);
+        let balances: Vec<Balance> = claims
+            .iter()
+            .zip([100, 200])
+            .map(|(claim, amount)| Balance {
+                account: get_associated_token_address_with_program_id(
+                    &PROTOCOL_WALLET,
+                    &claim.base_coin,
+                    &claim.token_program_base_coin,
+                ),
+                owner: PROTOCOL_WALLET,
+                pre: 0,
+                post: amount,
+            })
+            .collect();
+        let events = decode(&ix, &balances);
+        assert_eq!(events.len(), 2);
+        for ((event, claim), amount) in events.iter().zip(&claims).zip([100, 200]) {
+            assert_eq!(event.kind, "claim_protocol_fees");
+            assert_eq!(event.signer, payer);
+            assert_eq!(event.authority, Some(pda::authority(&claim.mint).0));
+            assert_eq!(event.base_coin_amount, Some(amount));
+            assert_eq!(event.recipient, Some(PROTOCOL_WALLET));
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::{thread, time::Duration};
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::RpcTransactionConfig,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::{
+    EncodedTransactionWithStatusMeta, UiInstruction, UiLoadedAddresses, UiTransactionEncoding,
+};
+
+mod db;
+mod decode;
+
+use db::Db;
+use decode::{decode_instruction, Event, TransactionContext};
+
+/// Replays candy wrapper transactions into a SQLite database.
+#[derive(Parser)]
+#[command(name = "candy-wrapper-indexer")]
+struct Cli {
+    /// RPC URL of the cluster
+    #[arg(long, short, default_value = "http://localhost:8899")]
+    url: String,
+    /// SQLite database, created when missing
+    #[arg(long, default_value = "candy-wrapper.db")]
+    db: String,
+    /// Seconds between two polls for new transactions
+    #[arg(long, default_value_t = 30)]
+    interval: u64,
+    /// Index what is available and exit
+    #[arg(long)]
+    once: bool,
+}
+
+struct Indexer {
+    client: RpcClient,
+    db: Db,
+}
+
+impl Indexer {
+    // signatures of the program's transactions since `until`, oldest first
+    fn new_signatures(&self, until: Option<Signature>) -> Result<Vec<(Signature, bool)>> {
+        let mut signatures = vec![];
+        let mut before = None;
+        loop {
+            let page = self.client.get_signatures_for_address_with_config(
+                &candy_wrapper::ID,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: None,
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            before = Some(last.signature.parse()?);
+            for status in page {
+                signatures.push((status.signature.parse()?, status.err.is_none()));
+            }
+        }
+        signatures.reverse();
+        Ok(signatures)
+    }
+
+    fn index_transaction(&mut self, signature: &Signature, succeeded: bool) -> Result<usize> {
+        let confirmed = self.client.get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            },
+        )?;
+        // failed transactions are recorded without events so they are not fetched again
+        let events = if succeeded {
+            decode_transaction(&confirmed.transaction)?
+        } else {
+            vec![]
+        };
+        self.db.insert_transaction(
+            &signature.to_string(),
+            confirmed.slot,
+            confirmed.block_time,
+            &events,
+        )?;
+        Ok(events.len())
+    }
+
+    fn run_once(&mut self) -> Result<()> {
+        let until = self
+            .db
+            .latest_signature()?
+            .map(|signature| signature.parse())
+            .transpose()?;
+        let signatures = self.new_signatures(until)?;
+        for (signature, succeeded) in &signatures {
+            let events = self.index_transaction(signature, *succeeded)?;
+            println!("{signature}: {events} events");
+        }
+        Ok(())
+    }
+}
+
+fn decode_transaction(transaction: &EncodedTransactionWithStatusMeta) -> Result<Vec<Event>> {
+    let versioned = transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| anyhow!("failed to decode transaction"))?;
+    let meta = transaction
+        .meta
+        .as_ref()
+        .ok_or_else(|| anyhow!("transaction has no status meta"))?;
+
+    let mut account_keys = versioned.message.static_account_keys().to_vec();
+    let loaded_addresses: Option<UiLoadedAddresses> = meta.loaded_addresses.clone().into();
+    if let Some(loaded) = loaded_addresses {
+        for key in loaded.writable.iter().chain(loaded.readonly.iter()) {
+            account_keys.push(key.parse()?);
+        }
+    }
+    let tx = TransactionContext {
+        account_keys,
+        pre_token_balances: Option::from(meta.pre_token_balances.clone()).unwrap_or_default(),
+        post_token_balances: Option::from(meta.post_token_balances.clone()).unwrap_or_default(),
+    };
+    let is_program = |program_id_index: u8| {
+        tx.account_keys.get(program_id_index as usize) == Some(&candy_wrapper::ID)
+    };
+
+    let mut events = vec![];
+    for instruction in versioned.message.instructions() {
+        if is_program(instruction.program_id_index) {
+            events.extend(decode_instruction(
+                &tx,
+                &instruction.accounts,
+                &instruction.data,
+            ));
+        }
+    }
+    // instructions invoked by other programs
+    let inner_instructions: Option<Vec<_>> = Option::from(meta.inner_instructions.clone());
+    for inner in inner_instructions.unwrap_or_default() {
+        for instruction in inner.instructions {
+            if let UiInstruction::Compiled(instruction) = instruction {
+                if is_program(instruction.program_id_index) {
+                    let data = bs58::decode(&instruction.data).into_vec()?;
+                    events.extend(decode_instruction(&tx, &instruction.accounts, &data));
+                }
+            }
+        }
+    }
+    Ok(events)
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut indexer = Indexer {
+        client: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+        db: Db::open(&cli.db)?,
+    };
+
+    if cli.once {
+        return indexer.run_once();
+    }
+    loop {
+        if let Err(err) = indexer.run_once() {
+            eprintln!("Failed to index transactions: {err:#}");
+        }
+        thread::sleep(Duration::from_secs(cli.interval));
+    }
+}
@@ -0,0 +1,279 @@
+use std::{thread, time::Duration};
+
+use anchor_spl::token_2022::spl_token_2022::{
+    self, extension::transfer_fee::instruction::harvest_withheld_tokens_to_mint,
+};
+use anyhow::{anyhow, Result};
+use candy_wrapper_client::{
+    instructions::{withdraw_fees, withdraw_fees_mint_transfers, withdraw_fees_transfers},
+    pda,
+    rpc::{
+        fetch_authorities, fetch_mint_withheld_amount, fetch_token_program,
+        fetch_transfer_hook_accounts, fetch_withheld_token_accounts,
+    },
+    state::fee_payout,
+    Authority, FeePayout,
+};
+use clap::Parser;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+/// Harvests withheld transfer fees of every candy wrapper mint and withdraws them once enough
+/// has accrued.
+#[derive(Parser)]
+#[command(name = "candy-wrapper-keeper")]
+struct Cli {
+    /// RPC URL of the cluster
+    #[arg(long, short, default_value = "http://localhost:8899")]
+    url: String,
+    /// Keypair paying for the cranks, defaults to the Solana CLI keypair
+    #[arg(long, short)]
+    keypair: Option<String>,
+    /// Holder accounts harvested per transaction
+    #[arg(long, default_value_t = 20)]
+    batch_size: usize,
+    /// Fees accrued by a mint, in base units, before `withdraw_fees` is called
+    #[arg(long, default_value_t = 1)]
+    threshold: u64,
+    /// Seconds between two passes over all mints
+    #[arg(long, default_value_t = 60)]
+    interval: u64,
+    /// Run a single pass and exit
+    #[arg(long)]
+    once: bool,
+}
+
+struct Keeper {
+    client: RpcClient,
+    payer: Keypair,
+    batch_size: usize,
+    threshold: u64,
+}
+
+impl Keeper {
+    fn send(&self, instructions: &[Instruction]) -> Result<Signature> {
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            self.client.get_latest_blockhash()?,
+        );
+        Ok(self.client.send_and_confirm_transaction(&transaction)?)
+    }
+
+    fn run_once(&self) -> Result<()> {
+        let authorities = fetch_authorities(&self.client)?;
+        println!("Cranking {} mints", authorities.len());
+        for (_, authority) in authorities {
+            // one failing mint must not hold back the others
+            if let Err(err) = self.crank(&authority) {
+                eprintln!("Failed to crank {}: {err:#}", authority.mint);
+            }
+        }
+        Ok(())
+    }
+
+    fn crank(&self, authority: &Authority) -> Result<()> {
+        let mint = authority.mint;
+
+        let holders = fetch_withheld_token_accounts(&self.client, &mint)?;
+        for batch in holders.chunks(self.batch_size.max(1)) {
+            let sources: Vec<&Pubkey> = batch.iter().map(|(address, _)| address).collect();
+            let signature = self.send(&[harvest_withheld_tokens_to_mint(
+                &spl_token_2022::ID,
+                &mint,
+                &sources,
+            )?])?;
+            let harvested: u64 = batch.iter().map(|(_, withheld)| withheld).sum();
+            println!(
+                "{mint}: harvested {harvested} from {} accounts: {signature}",
+                batch.len()
+            );
+        }
+
+        let accrued = self.accrued_fees(authority)?;
+        if !should_withdraw(accrued, self.threshold) {
+            return Ok(());
+        }
+
+        let token_program_base_coin = fetch_token_program(&self.client, &authority.base_coin)?;
+        let payer = self.payer.pubkey();
+        let fee_payout = fee_payout(authority);
+        let mut ixs = create_fee_collector_accounts(&payer, authority, &token_program_base_coin);
+        let mut ix = withdraw_fees(
+            &payer,
+            &mint,
+            &authority.base_coin,
+            &token_program_base_coin,
+            &authority.fee_collector,
+            fee_payout,
+        );
+        // accounts of the transfer hooks of the base coin and the mint, if they have one
+        ix.accounts.extend(fetch_transfer_hook_accounts(
+            &self.client,
+            &authority.base_coin,
+            &withdraw_fees_transfers(
+                &mint,
+                &authority.base_coin,
+                &token_program_base_coin,
+                &authority.fee_collector,
+            ),
+            accrued,
+        )?);
+        if fee_payout == FeePayout::Mint {
+            ix.accounts.extend(fetch_transfer_hook_accounts(
+                &self.client,
+                &mint,
+                &withdraw_fees_mint_transfers(&mint, &authority.fee_collector),
+                accrued,
+            )?);
+        }
+        ixs.push(ix);
+        let signature = self.send(&ixs)?;
+        println!("{mint}: withdrew {accrued} in fees: {signature}");
+        Ok(())
+    }
+
+    fn accrued_fees(&self, authority: &Authority) -> Result<u64> {
+        let withheld = fetch_mint_withheld_amount(&self.client, &authority.mint)?;
+        let fee_vault: u64 = self
+            .client
+            .get_token_account_balance(&pda::fee_vault(&authority.mint).0)?
+            .amount
+            .parse()?;
+        Ok(accrued_fees(authority, withheld, fee_vault))
+    }
+}
+
+// transfer fees withheld in the mint plus issuance and redemption fees in the fee vault that
+// are not owed to the protocol yet
+fn accrued_fees(authority: &Authority, withheld: u64, fee_vault: u64) -> u64 {
+    withheld.saturating_add(fee_vault.saturating_sub(authority.protocol_fees_accrued))
+}
+
+// a withdrawal with nothing to pay out only costs the keeper its fee
+fn should_withdraw(accrued: u64, threshold: u64) -> bool {
+    accrued > 0 && accrued >= threshold
+}
+
+// the fee collector's token accounts may not exist yet
+fn create_fee_collector_accounts(
+    payer: &Pubkey,
+    authority: &Authority,
+    token_program_base_coin: &Pubkey,
+) -> Vec<Instruction> {
+    let mut ixs = vec![create_associated_token_account_idempotent(
+        payer,
+        &authority.fee_collector,
+        &authority.base_coin,
+        token_program_base_coin,
+    )];
+    if fee_payout(authority) == FeePayout::Mint {
+        ixs.push(create_associated_token_account_idempotent(
+            payer,
+            &authority.fee_collector,
+            &authority.mint,
+            &spl_token_2022::ID,
+        ));
+    }
+    ixs
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let keypair = cli.keypair.unwrap_or_else(|| {
+        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        format!("{home}/.config/solana/id.json")
+    });
+    let keeper = Keeper {
+        client: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+        payer: read_keypair_file(&keypair)
+            .map_err(|err| anyhow!("failed to read keypair {keypair}: {err}"))?,
+        batch_size: cli.batch_size,
+        threshold: cli.threshold,
+    };
+
+    if cli.once {
+        return keeper.run_once();
+    }
+    loop {
+        if let Err(err) = keeper.run_once() {
+            eprintln!("Failed to list mints: {err:#}");
+        }
+        thread::sleep(Duration::from_secs(cli.interval));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+    use bytemuck::Zeroable;
+
+    use super::*;
+
+    fn authority(fee_payout: FeePayout, protocol_fees_accrued: u64) -> Authority {
+        let mut authority = Authority::zeroed();
+        authority.mint = Pubkey::new_unique();
+        authority.base_coin = Pubkey::new_unique();
+        authority.fee_collector = Pubkey::new_unique();
+        authority.fee_payout = fee_payout as u8;
+        authority.protocol_fees_accrued = protocol_fees_accrued;
+        authority
+    }
+
+    #[test]
+    fn accrued_fees_leave_out_the_protocol_share() {
+        let authority = authority(FeePayout::BaseCoin, 30);
+        assert_eq!(accrued_fees(&authority, 500, 100), 570);
+        assert_eq!(accrued_fees(&authority, 0, 30), 0);
+        // a fee vault short of the protocol's share has nothing for the fee collector
+        assert_eq!(accrued_fees(&authority, 500, 10), 500);
+        assert_eq!(accrued_fees(&authority, u64::MAX, 100), u64::MAX);
+    }
+
+    #[test]
+    fn withdraws_once_accrued_fees_reach_the_threshold() {
+        assert!(!should_withdraw(999, 1_000));
+        assert!(should_withdraw(1_000, 1_000));
+        assert!(should_withdraw(1, 1));
+        assert!(!should_withdraw(0, 0));
+        assert!(should_withdraw(1, 0));
+    }
+
+    #[test]
+    fn creates_the_fee_collector_accounts_of_its_payout() {
+        let payer = Pubkey::new_unique();
+        let token_program_base_coin = anchor_spl::token::ID;
+        let created = |authority: &Authority| {
+            create_fee_collector_accounts(&payer, authority, &token_program_base_coin)
+                .iter()
+                .map(|ix| ix.accounts[1].pubkey)
+                .collect::<Vec<_>>()
+        };
+
+        let authority = authority(FeePayout::BaseCoin, 0);
+        let base_coin_account = get_associated_token_address_with_program_id(
+            &authority.fee_collector,
+            &authority.base_coin,
+            &token_program_base_coin,
+        );
+        assert_eq!(created(&authority), [base_coin_account]);
+
+        let mut authority = authority;
+        authority.fee_payout = FeePayout::Mint as u8;
+        let mint_account = get_associated_token_address_with_program_id(
+            &authority.fee_collector,
+            &authority.mint,
+            &spl_token_2022::ID,
+        );
+        assert_eq!(created(&authority), [base_coin_account, mint_account]);
+    }
+}
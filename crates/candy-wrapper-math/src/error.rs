@@ -0,0 +1,18 @@
+use core::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    Overflow,
+    DivisionByZero,
+    EmptySupply,
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MathError::Overflow => f.write_str("math overflow"),
+            MathError::DivisionByZero => f.write_str("division by zero"),
+            MathError::EmptySupply => f.write_str("mint supply is empty"),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{ceil_div, to_u64, MathError, MathResult, ONE_IN_BASIS_PTS};
+
+/// Fee charged on `amount`, rounded up so that rounding never favours the payer.
+pub fn calculate_fee(amount: u64, fee_basis_pts: u16) -> MathResult<u64> {
+    if fee_basis_pts == 0 || amount == 0 {
+        return Ok(0);
+    }
+    let numerator = (amount as u128)
+        .checked_mul(fee_basis_pts as u128)
+        .ok_or(MathError::Overflow)?;
+    to_u64(ceil_div(numerator, ONE_IN_BASIS_PTS)?)
+}
+
+/// Share of `amount` owed to a recipient, rounded down so that the shares of a split never
+/// add up to more than `amount`.
+pub fn calculate_share(amount: u64, share_basis_pts: u16) -> MathResult<u64> {
+    let share = (amount as u128)
+        .checked_mul(share_basis_pts as u128)
+        .ok_or(MathError::Overflow)?
+        / ONE_IN_BASIS_PTS;
+    to_u64(share)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProtocolFeeSplit {
+    pub protocol_fee: u64,
+    pub amount_after_protocol_fee: u64,
+}
+
+/// Splits a fee between the protocol and the fee collector. The two parts always add up to
+/// `fee`.
+pub fn split_protocol_fee(fee: u64, protocol_fee_basis_pts: u16) -> MathResult<ProtocolFeeSplit> {
+    let protocol_fee = calculate_fee(fee, protocol_fee_basis_pts)?.min(fee);
+    Ok(ProtocolFeeSplit {
+        protocol_fee,
+        amount_after_protocol_fee: fee - protocol_fee,
+    })
+}
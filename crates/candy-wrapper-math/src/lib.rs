@@ -0,0 +1,34 @@
+#![no_std]
+
+mod error;
+mod fees;
+mod pricing;
+mod solvency;
+mod transfer_fee;
+
+pub use error::MathError;
+pub use fees::*;
+pub use pricing::*;
+pub use solvency::*;
+pub use transfer_fee::*;
+
+pub type MathResult<T> = Result<T, MathError>;
+
+/// 100% expressed in basis points.
+pub const MAX_BASIS_PTS: u16 = 10_000;
+const ONE_IN_BASIS_PTS: u128 = MAX_BASIS_PTS as u128;
+
+fn ceil_div(numerator: u128, denominator: u128) -> MathResult<u128> {
+    if denominator == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+    numerator
+        .checked_add(denominator)
+        .and_then(|n| n.checked_sub(1))
+        .map(|n| n / denominator)
+        .ok_or(MathError::Overflow)
+}
+
+fn to_u64(amount: u128) -> MathResult<u64> {
+    u64::try_from(amount).map_err(|_| MathError::Overflow)
+}
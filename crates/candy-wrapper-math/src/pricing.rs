@@ -0,0 +1,44 @@
+use crate::{to_u64, MathError, MathResult};
+
+/// Creator tokens minted for `base_coin_amount` deposited into a vault holding
+/// `authority_base_coin_amount` against `mint_supply_amount` outstanding tokens. The first
+/// deposit is priced at `mint_to_base_ratio`, later ones at the vault's current backing,
+/// rounded down.
+pub fn calculate_mint_amount(
+    base_coin_amount: u64,
+    mint_to_base_ratio: u16,
+    authority_base_coin_amount: u64,
+    mint_supply_amount: u64,
+) -> MathResult<u64> {
+    if mint_supply_amount > 0 {
+        if authority_base_coin_amount == 0 {
+            return Err(MathError::DivisionByZero);
+        }
+        let mint_amount = (base_coin_amount as u128)
+            .checked_mul(mint_supply_amount as u128)
+            .ok_or(MathError::Overflow)?
+            / authority_base_coin_amount as u128;
+        to_u64(mint_amount)
+    } else {
+        let mint_amount = (base_coin_amount as u128)
+            .checked_mul(mint_to_base_ratio as u128)
+            .ok_or(MathError::Overflow)?;
+        to_u64(mint_amount)
+    }
+}
+
+/// Base coin released from the vault for `mint_amount` creator tokens, rounded down.
+pub fn calculate_base_coin_amount(
+    mint_amount: u64,
+    authority_base_coin_amount: u64,
+    mint_supply_amount: u64,
+) -> MathResult<u64> {
+    if mint_supply_amount == 0 {
+        return Err(MathError::EmptySupply);
+    }
+    let base_coin_amount = (mint_amount as u128)
+        .checked_mul(authority_base_coin_amount as u128)
+        .ok_or(MathError::Overflow)?
+        / mint_supply_amount as u128;
+    to_u64(base_coin_amount)
+}
@@ -0,0 +1,38 @@
+use crate::ONE_IN_BASIS_PTS;
+
+/// Base coin held by a vault and the creator tokens it backs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Backing {
+    pub reserves: u64,
+    pub supply: u64,
+}
+
+impl Backing {
+    /// Whether the reserves cover the supply at `mint_to_base_ratio` tokens per base coin unit.
+    pub fn covers_peg(&self, mint_to_base_ratio: u16) -> bool {
+        self.reserves as u128 * mint_to_base_ratio as u128 >= self.supply as u128
+    }
+
+    /// Reserves relative to the peg in basis points, 10_000 when the supply is backed exactly
+    /// at `mint_to_base_ratio`. Rounds down and saturates at `u64::MAX`, which an empty
+    /// supply also reports as nothing is owed then.
+    pub fn ratio_basis_pts(&self, mint_to_base_ratio: u16) -> u64 {
+        if self.supply == 0 {
+            return u64::MAX;
+        }
+        // at most 2^64 * 2^16 * 2^14, so it cannot overflow
+        let ratio = self.reserves as u128 * mint_to_base_ratio as u128 * ONE_IN_BASIS_PTS
+            / self.supply as u128;
+        u64::try_from(ratio).unwrap_or(u64::MAX)
+    }
+
+    /// Whether each token is backed by at least as much base coin as it was `before`. Always
+    /// true when either side has no supply, as no token held a claim then.
+    pub fn is_at_least(&self, before: &Backing) -> bool {
+        if before.supply == 0 || self.supply == 0 {
+            return true;
+        }
+        self.reserves as u128 * before.supply as u128
+            >= before.reserves as u128 * self.supply as u128
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{calculate_fee, ceil_div, to_u64, MathError, MathResult, ONE_IN_BASIS_PTS};
+
+/// Mirrors a Token-2022 `TransferFee`, the fee schedule in force from `epoch` onwards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransferFee {
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub transfer_fee_basis_pts: u16,
+}
+
+impl TransferFee {
+    /// Fee withheld when `pre_fee_amount` is transferred.
+    pub fn calculate_fee(&self, pre_fee_amount: u64) -> MathResult<u64> {
+        Ok(calculate_fee(pre_fee_amount, self.transfer_fee_basis_pts)?.min(self.maximum_fee))
+    }
+
+    /// Amount that arrives when `pre_fee_amount` is transferred.
+    pub fn calculate_post_fee_amount(&self, pre_fee_amount: u64) -> MathResult<u64> {
+        // a fee above 100% would exceed the amount
+        pre_fee_amount
+            .checked_sub(self.calculate_fee(pre_fee_amount)?)
+            .ok_or(MathError::Overflow)
+    }
+
+    /// Smallest amount to transfer so that `post_fee_amount` arrives.
+    pub fn calculate_pre_fee_amount(&self, post_fee_amount: u64) -> MathResult<u64> {
+        let transfer_fee_basis_pts = self.transfer_fee_basis_pts as u128;
+        if transfer_fee_basis_pts == 0 || post_fee_amount == 0 {
+            return Ok(post_fee_amount);
+        }
+        if transfer_fee_basis_pts >= ONE_IN_BASIS_PTS {
+            return post_fee_amount
+                .checked_add(self.maximum_fee)
+                .ok_or(MathError::Overflow);
+        }
+        let numerator = (post_fee_amount as u128)
+            .checked_mul(ONE_IN_BASIS_PTS)
+            .ok_or(MathError::Overflow)?;
+        let raw_pre_fee_amount = ceil_div(numerator, ONE_IN_BASIS_PTS - transfer_fee_basis_pts)?;
+        if raw_pre_fee_amount - post_fee_amount as u128 >= self.maximum_fee as u128 {
+            post_fee_amount
+                .checked_add(self.maximum_fee)
+                .ok_or(MathError::Overflow)
+        } else {
+            to_u64(raw_pre_fee_amount)
+        }
+    }
+}
+
+/// Mirrors the fee schedules of a Token-2022 `TransferFeeConfig`. A fee change only takes
+/// effect from the epoch after it was made, so the older schedule applies until then.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransferFeeSchedule {
+    pub older_transfer_fee: TransferFee,
+    pub newer_transfer_fee: TransferFee,
+}
+
+impl TransferFeeSchedule {
+    pub fn get_epoch_fee(&self, epoch: u64) -> &TransferFee {
+        if epoch >= self.newer_transfer_fee.epoch {
+            &self.newer_transfer_fee
+        } else {
+            &self.older_transfer_fee
+        }
+    }
+
+    pub fn calculate_epoch_fee(&self, epoch: u64, pre_fee_amount: u64) -> MathResult<u64> {
+        self.get_epoch_fee(epoch).calculate_fee(pre_fee_amount)
+    }
+
+    pub fn calculate_post_fee_amount(&self, epoch: u64, pre_fee_amount: u64) -> MathResult<u64> {
+        self.get_epoch_fee(epoch)
+            .calculate_post_fee_amount(pre_fee_amount)
+    }
+
+    pub fn calculate_pre_fee_amount(&self, epoch: u64, post_fee_amount: u64) -> MathResult<u64> {
+        self.get_epoch_fee(epoch)
+            .calculate_pre_fee_amount(post_fee_amount)
+    }
+}
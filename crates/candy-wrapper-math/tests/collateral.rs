@@ -0,0 +1,263 @@
+use candy_wrapper_math::*;
+use proptest::prelude::*;
+
+// vault reserves and outstanding supply at the moment of an operation, valid when the
+// reserves cover the supply at the peg
+fn collateralised_state() -> impl Strategy<Value = (u16, u64, u64)> {
+    (1..=u16::MAX, 1..=u64::MAX / 4).prop_flat_map(|(ratio, reserves)| {
+        let max_supply = (reserves as u128 * ratio as u128).min(u64::MAX as u128 / 4) as u64;
+        (Just(ratio), Just(reserves), 1..=max_supply.max(1))
+    })
+}
+
+fn is_collateralised(ratio: u16, reserves: u64, supply: u64) -> bool {
+    reserves as u128 * ratio as u128 >= supply as u128
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    Issue(u64),
+    Redeem(u64),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (1..=1_000_000_000_000u64).prop_map(Op::Issue),
+        any::<u64>().prop_map(Op::Redeem),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn issuance_never_dilutes_backing(
+        (ratio, reserves, supply) in collateralised_state(),
+        deposit in 0..=u64::MAX / 4,
+    ) {
+        let minted = calculate_mint_amount(deposit, ratio, reserves, supply);
+        prop_assume!(minted.is_ok());
+        let new_supply = supply.checked_add(minted.unwrap());
+        prop_assume!(new_supply.is_some());
+        let (new_reserves, new_supply) = (reserves + deposit, new_supply.unwrap());
+
+        prop_assert!(is_collateralised(ratio, new_reserves, new_supply));
+        // backing per token is at least what it was before
+        prop_assert!(
+            new_reserves as u128 * supply as u128 >= reserves as u128 * new_supply as u128
+        );
+    }
+
+    #[test]
+    fn redemption_never_dilutes_backing(
+        (ratio, reserves, supply) in collateralised_state(),
+        redeem_bps in 0..=MAX_BASIS_PTS,
+    ) {
+        let redeemed = calculate_share(supply, redeem_bps).unwrap();
+        let released = calculate_base_coin_amount(redeemed, reserves, supply).unwrap();
+        prop_assert!(released <= reserves);
+        let (new_reserves, new_supply) = (reserves - released, supply - redeemed);
+
+        prop_assert!(is_collateralised(ratio, new_reserves, new_supply));
+        prop_assert!(
+            new_reserves as u128 * supply as u128 >= reserves as u128 * new_supply as u128
+        );
+    }
+
+    #[test]
+    fn backing_agrees_with_pricing(
+        (ratio, reserves, supply) in collateralised_state(),
+        redeem_bps in 0..=MAX_BASIS_PTS,
+        deposit in 0..=u64::MAX / 4,
+    ) {
+        let before = Backing { reserves, supply };
+        prop_assert!(before.covers_peg(ratio));
+
+        let redeemed = calculate_share(supply, redeem_bps).unwrap();
+        let released = calculate_base_coin_amount(redeemed, reserves, supply).unwrap();
+        let after = Backing { reserves: reserves - released, supply: supply - redeemed };
+        prop_assert!(after.covers_peg(ratio) && after.is_at_least(&before));
+
+        let minted = calculate_mint_amount(deposit, ratio, reserves, supply);
+        prop_assume!(minted.is_ok());
+        let new_supply = supply.checked_add(minted.unwrap());
+        prop_assume!(new_supply.is_some());
+        let after = Backing { reserves: reserves + deposit, supply: new_supply.unwrap() };
+        prop_assert!(after.covers_peg(ratio) && after.is_at_least(&before));
+    }
+
+    #[test]
+    fn ratio_basis_pts_agrees_with_peg(
+        ratio in 1..=u16::MAX,
+        reserves in any::<u64>(),
+        supply in any::<u64>(),
+    ) {
+        let backing = Backing { reserves, supply };
+        prop_assert_eq!(
+            backing.ratio_basis_pts(ratio) >= MAX_BASIS_PTS as u64,
+            backing.covers_peg(ratio)
+        );
+    }
+
+    #[test]
+    fn last_redeemer_receives_the_whole_vault(
+        (_ratio, reserves, supply) in collateralised_state(),
+    ) {
+        prop_assert_eq!(calculate_base_coin_amount(supply, reserves, supply).unwrap(), reserves);
+    }
+
+    #[test]
+    fn vault_stays_collateralised_over_any_sequence(
+        ratio in 1..=1_000u16,
+        issuance_fee_basis_pts in 0..=100u16,
+        redemption_fee_basis_pts in 0..=100u16,
+        ops in prop::collection::vec(op(), 1..64),
+    ) {
+        let (mut reserves, mut supply) = (0u64, 0u64);
+        for op in ops {
+            match op {
+                Op::Issue(amount) => {
+                    let fee = calculate_fee(amount, issuance_fee_basis_pts).unwrap();
+                    let deposit = amount - fee;
+                    let minted = calculate_mint_amount(deposit, ratio, reserves, supply).unwrap();
+                    reserves += deposit;
+                    supply += minted;
+                }
+                Op::Redeem(amount) => {
+                    if supply == 0 {
+                        continue;
+                    }
+                    let amount = amount % supply + 1;
+                    let released = calculate_base_coin_amount(amount, reserves, supply).unwrap();
+                    let fee = calculate_fee(released, redemption_fee_basis_pts).unwrap();
+                    prop_assert!(fee <= released);
+                    // the fee leaves the vault too, it goes to the fee vault
+                    reserves -= released;
+                    supply -= amount;
+                }
+            }
+            prop_assert!(is_collateralised(ratio, reserves, supply));
+        }
+    }
+
+    #[test]
+    fn fee_is_bounded_by_amount(amount in any::<u64>(), fee_basis_pts in 0..=MAX_BASIS_PTS) {
+        prop_assert!(calculate_fee(amount, fee_basis_pts).unwrap() <= amount);
+    }
+
+    #[test]
+    fn protocol_split_adds_up(fee in any::<u64>(), protocol_fee_basis_pts in 0..=MAX_BASIS_PTS) {
+        let split = split_protocol_fee(fee, protocol_fee_basis_pts).unwrap();
+        prop_assert_eq!(split.protocol_fee + split.amount_after_protocol_fee, fee);
+    }
+
+    #[test]
+    fn shares_never_exceed_amount(
+        amount in any::<u64>(),
+        shares in prop::collection::vec(0..=MAX_BASIS_PTS, 1..8),
+    ) {
+        let total_basis_pts: u32 = shares.iter().map(|s| *s as u32).sum();
+        prop_assume!(total_basis_pts <= MAX_BASIS_PTS as u32);
+        let total: u128 = shares
+            .iter()
+            .map(|s| calculate_share(amount, *s).unwrap() as u128)
+            .sum();
+        prop_assert!(total <= amount as u128);
+    }
+
+    #[test]
+    fn transfer_fee_is_capped(
+        amount in any::<u64>(),
+        transfer_fee_basis_pts in 0..=MAX_BASIS_PTS,
+        maximum_fee in any::<u64>(),
+    ) {
+        let transfer_fee = TransferFee { epoch: 0, maximum_fee, transfer_fee_basis_pts };
+        let fee = transfer_fee.calculate_fee(amount).unwrap();
+        prop_assert!(fee <= amount && fee <= maximum_fee);
+    }
+
+    #[test]
+    fn pre_fee_amount_delivers_post_fee_amount(
+        post_fee_amount in 0..=u64::MAX / 2,
+        transfer_fee_basis_pts in 0..MAX_BASIS_PTS,
+        maximum_fee in 0..=u64::MAX / 2,
+    ) {
+        let transfer_fee = TransferFee { epoch: 0, maximum_fee, transfer_fee_basis_pts };
+        let pre_fee_amount = transfer_fee.calculate_pre_fee_amount(post_fee_amount).unwrap();
+        prop_assert!(transfer_fee.calculate_post_fee_amount(pre_fee_amount).unwrap() >= post_fee_amount);
+    }
+
+    #[test]
+    fn post_fee_amount_rejects_fees_above_the_amount(
+        amount in 1..=u64::MAX,
+        transfer_fee_basis_pts in MAX_BASIS_PTS + 1..=u16::MAX,
+        maximum_fee in any::<u64>(),
+    ) {
+        let transfer_fee = TransferFee { epoch: 0, maximum_fee, transfer_fee_basis_pts };
+        match transfer_fee.calculate_fee(amount) {
+            Ok(fee) if fee <= amount => prop_assert_eq!(
+                transfer_fee.calculate_post_fee_amount(amount),
+                Ok(amount - fee)
+            ),
+            _ => prop_assert_eq!(
+                transfer_fee.calculate_post_fee_amount(amount),
+                Err(MathError::Overflow)
+            ),
+        }
+    }
+
+    #[test]
+    fn newer_fee_applies_from_its_epoch(
+        epoch in any::<u64>(),
+        newer_epoch in any::<u64>(),
+        amount in any::<u64>(),
+    ) {
+        let schedule = TransferFeeSchedule {
+            older_transfer_fee: TransferFee { epoch: 0, maximum_fee: u64::MAX, transfer_fee_basis_pts: 10 },
+            newer_transfer_fee: TransferFee { epoch: newer_epoch, maximum_fee: u64::MAX, transfer_fee_basis_pts: 20 },
+        };
+        let expected = if epoch >= newer_epoch { 20 } else { 10 };
+        prop_assert_eq!(
+            schedule.calculate_epoch_fee(epoch, amount).unwrap(),
+            calculate_fee(amount, expected).unwrap()
+        );
+    }
+}
+
+#[test]
+fn backing_detects_drained_vaults() {
+    let before = Backing {
+        reserves: 1_000,
+        supply: 100_000,
+    };
+    let fair = Backing {
+        reserves: 900,
+        supply: 90_000,
+    };
+    let drained = Backing {
+        reserves: 899,
+        supply: 90_000,
+    };
+    assert!(fair.covers_peg(100) && fair.is_at_least(&before));
+    assert!(!drained.covers_peg(100) && !drained.is_at_least(&before));
+    assert_eq!(fair.ratio_basis_pts(100), 10_000);
+    assert_eq!(drained.ratio_basis_pts(100), 9_988);
+    assert_eq!(
+        Backing {
+            reserves: 0,
+            supply: 0
+        }
+        .ratio_basis_pts(100),
+        u64::MAX
+    );
+}
+
+#[test]
+fn pricing_rejects_empty_vaults() {
+    assert_eq!(
+        calculate_base_coin_amount(1, 1, 0),
+        Err(MathError::EmptySupply)
+    );
+    assert_eq!(
+        calculate_mint_amount(1, 1, 0, 1),
+        Err(MathError::DivisionByZero)
+    );
+}
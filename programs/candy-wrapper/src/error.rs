@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use candy_wrapper_math::MathError;
+
 // An enum for custom error codes
 #[error_code]
 pub enum CustomError {
@@ -13,4 +15,47 @@ pub enum CustomError {
     MintIsImmutable,
     MintIsNotZero,
     BaseCoinIsNotZero,
+    FeeSplitRecipientsCannotExceedMax,
+    FeeSplitBasisPtsMustTotal10000,
+    IncorrectFeeSplitRecipient,
+    MissingFeeCollectorMintTokenAccount,
+    AuthorityIsNotLegacy,
+    IncorrectProtocolFeeClaimAccounts,
+    MathOverflow,
+    DivisionByZero,
+    EmptySupply,
+    ZeroOutput,
+    Undercollateralized,
+    IncorrectTransferHookAuthority,
+    TransferHookTakenByProgram,
+    MissingTransferHookAccounts,
+    MerchantAlreadyRegistered,
+    MerchantNotRegistered,
+    MerchantsCannotExceedMax,
+    TransferRequiresMerchant,
+    TransferHookCannotPayFeesInMint,
+    IncorrectComplianceOfficer,
+    WalletIsBlocked,
+    DefaultFrozenRequiresFreezable,
+    MintIsNotFreezable,
+    NonTransferableCannotPayFeesInMint,
+    MintIsNonTransferable,
+    NonTransferableCannotChargeTransferFees,
+    FeeVaultIsNotEmpty,
+    MintIsNotSigner,
+    IncorrectMintSeed,
+    FeeCollectorIsFeeSplit,
+    FeeSplitCannotPayFeesInMint,
+    MissingClosableAccount,
+    WalletsAreStillBlocked,
+}
+
+impl From<MathError> for CustomError {
+    fn from(error: MathError) -> Self {
+        match error {
+            MathError::Overflow => CustomError::MathOverflow,
+            MathError::DivisionByZero => CustomError::DivisionByZero,
+            MathError::EmptySupply => CustomError::EmptySupply,
+        }
+    }
 }
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct ComplianceOfficerChanged {
+    pub mint: Pubkey,
+    pub officer: Pubkey,
+}
+
+#[event]
+pub struct WalletBlocked {
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+    pub officer: Pubkey,
+}
+
+#[event]
+pub struct WalletUnblocked {
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+    pub officer: Pubkey,
+}
+
+#[event]
+pub struct AccountFrozen {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub officer: Pubkey,
+}
+
+#[event]
+pub struct AccountThawed {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub officer: Pubkey,
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    error::CustomError,
+    state::{Authority, MerchantRegistry, MAX_MERCHANTS},
+};
+
+#[derive(Accounts)]
+pub struct MerchantCtx<'info> {
+    #[account(
+        mut,
+        constraint = payer.key() == authority.load()?.admin @CustomError::IncorrectUpdateAuthority,
+    )]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [b"authority", mint.key().as_ref()],
+        bump = authority.load()?.bump,
+    )]
+    pub authority: AccountLoader<'info, Authority>,
+    #[account(
+        constraint = mint.key() == authority.load()?.mint @CustomError::IncorrectMint,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"merchant_registry", mint.key().as_ref()],
+        bump = merchant_registry.bump,
+    )]
+    pub merchant_registry: Account<'info, MerchantRegistry>,
+}
+
+pub fn add_merchant_handler(ctx: Context<MerchantCtx>, wallet: Pubkey) -> Result<()> {
+    let merchants = &mut ctx.accounts.merchant_registry.merchants;
+    require!(
+        !merchants.contains(&wallet),
+        CustomError::MerchantAlreadyRegistered
+    );
+    require!(
+        merchants.len() < MAX_MERCHANTS,
+        CustomError::MerchantsCannotExceedMax
+    );
+    merchants.push(wallet);
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    error::CustomError,
+    state::{AttestationRecord, Authority, ReserveAttestation, RESERVE_ATTESTATION_SPACE},
+    utils::{backing, get_withheld_fee},
+};
+
+#[derive(Accounts)]
+pub struct AttestReservesCtx<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        constraint = mint.key() == authority.load()?.mint @CustomError::IncorrectMint,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        constraint = base_coin.key() == authority.load()?.base_coin @CustomError::UnauthorizedBaseCoin,
+    )]
+    pub base_coin: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [b"authority", mint.key().as_ref()],
+        bump = authority.load()?.bump,
+    )]
+    pub authority: AccountLoader<'info, Authority>,
+    #[account(
+        associated_token::mint = base_coin,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program_base_coin,
+    )]
+    pub authority_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = RESERVE_ATTESTATION_SPACE,
+        seeds = [b"reserve_attestation", mint.key().as_ref()],
+        bump,
+    )]
+    pub reserve_attestation: AccountLoader<'info, ReserveAttestation>,
+    pub token_program_base_coin: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn attest_reserves_handler(ctx: Context<AttestReservesCtx>) -> Result<()> {
+    let clock = Clock::get()?;
+    let backing = backing(
+        &ctx.accounts.authority_base_coin_token_account,
+        &ctx.accounts.mint,
+    );
+    let record = AttestationRecord {
+        reserves: backing.reserves,
+        supply: backing.supply,
+        withheld: get_withheld_fee(&ctx.accounts.mint.to_account_info())?,
+        backing_ratio_basis_pts: backing
+            .ratio_basis_pts(ctx.accounts.authority.load()?.mint_to_base_ratio),
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+    };
+
+    // a new account has no discriminator until the instruction exits
+    let attestation = &mut match ctx.accounts.reserve_attestation.load_mut() {
+        Ok(attestation) => attestation,
+        Err(_) => {
+            let mut attestation = ctx.accounts.reserve_attestation.load_init()?;
+            attestation.mint = ctx.accounts.mint.key();
+            attestation.bump = ctx.bumps.reserve_attestation;
+            attestation
+        }
+    };
+    attestation.push(record);
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    error::CustomError,
+    events::WalletBlocked,
+    state::{BlocklistEntry, Compliance, BLOCKLIST_ENTRY_SPACE},
+};
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct BlockWalletCtx<'info> {
+    #[account(
+        mut,
+        constraint = officer.key() == compliance.officer @CustomError::IncorrectComplianceOfficer,
+    )]
+    pub officer: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"compliance", mint.key().as_ref()],
+        bump = compliance.bump,
+    )]
+    pub compliance: Account<'info, Compliance>,
+    #[account(
+        init,
+        payer = officer,
+        space = BLOCKLIST_ENTRY_SPACE,
+        seeds = [b"blocklist", mint.key().as_ref(), wallet.as_ref()],
+        bump,
+    )]
+    pub blocklist_entry: Account<'info, BlocklistEntry>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn block_wallet_handler(ctx: Context<BlockWalletCtx>, wallet: Pubkey) -> Result<()> {
+    let blocklist_entry = &mut ctx.accounts.blocklist_entry;
+    blocklist_entry.bump = ctx.bumps.blocklist_entry;
+    blocklist_entry.mint = ctx.accounts.mint.key();
+    blocklist_entry.wallet = wallet;
+    let compliance = &mut ctx.accounts.compliance;
+    compliance.blocked = compliance
+        .blocked
+        .checked_add(1)
+        .ok_or(CustomError::MathOverflow)?;
+
+    emit!(WalletBlocked {
+        mint: blocklist_entry.mint,
+        wallet,
+        officer: ctx.accounts.officer.key(),
+    });
+    Ok(())
+}
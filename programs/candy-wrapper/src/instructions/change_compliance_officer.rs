@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    error::CustomError,
+    events::ComplianceOfficerChanged,
+    state::{Authority, Compliance, COMPLIANCE_SPACE},
+};
+
+#[derive(Accounts)]
+pub struct ComplianceOfficerCtx<'info> {
+    #[account(
+        mut,
+        constraint = payer.key() == authority.load()?.admin @CustomError::IncorrectUpdateAuthority,
+    )]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [b"authority", mint.key().as_ref()],
+        bump = authority.load()?.bump,
+    )]
+    pub authority: AccountLoader<'info, Authority>,
+    #[account(
+        constraint = mint.key() == authority.load()?.mint @CustomError::IncorrectMint,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = COMPLIANCE_SPACE,
+        seeds = [b"compliance", mint.key().as_ref()],
+        bump,
+    )]
+    pub compliance: Account<'info, Compliance>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn change_compliance_officer_handler(
+    ctx: Context<ComplianceOfficerCtx>,
+    officer: Pubkey,
+) -> Result<()> {
+    require!(
+        ctx.accounts.authority.load()?.mutable == 1,
+        CustomError::MintIsImmutable
+    );
+    let compliance = &mut ctx.accounts.compliance;
+    compliance.bump = ctx.bumps.compliance;
+    compliance.mint = ctx.accounts.mint.key();
+    compliance.officer = officer;
+
+    emit!(ComplianceOfficerChanged {
+        mint: compliance.mint,
+        officer,
+    });
+    Ok(())
+}
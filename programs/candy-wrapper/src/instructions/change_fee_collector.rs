@@ -6,7 +6,7 @@ use crate::{error::CustomError, state::Authority};
 pub struct FeeCollectorCtx<'info> {
     #[account(
         mut,
-        constraint = payer.key() == authority.load()?.admin,
+        constraint = payer.key() == authority.load()?.admin @CustomError::IncorrectUpdateAuthority,
     )]
     pub payer: Signer<'info>,
     #[account(mut)]
@@ -19,6 +19,15 @@ pub fn change_fee_collector_handler(
 ) -> Result<()> {
     let authority = &mut ctx.accounts.authority.load_mut()?;
     require!(authority.mutable == 1, CustomError::MintIsImmutable);
+    // a fee split's recipients are changed with `set_fee_split`, and it is replaced as the fee
+    // collector with `clear_fee_split`, which pays out what it holds first
+    let (fee_split, _) =
+        Pubkey::find_program_address(&[b"fee_split", authority.mint.as_ref()], &crate::ID);
+    require_keys_neq!(
+        authority.fee_collector,
+        fee_split,
+        CustomError::FeeCollectorIsFeeSplit
+    );
     authority.fee_collector = new_fee_collector;
     Ok(())
 }
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::CustomError,
+    state::{Authority, FeePayout},
+};
+
+#[derive(Accounts)]
+pub struct FeePayoutCtx<'info> {
+    #[account(
+        mut,
+        constraint = payer.key() == authority.load()?.admin @CustomError::IncorrectUpdateAuthority,
+    )]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub authority: AccountLoader<'info, Authority>,
+}
+
+pub fn change_fee_payout_handler(ctx: Context<FeePayoutCtx>, fee_payout: FeePayout) -> Result<()> {
+    let authority = &mut ctx.accounts.authority.load_mut()?;
+    require!(authority.mutable == 1, CustomError::MintIsImmutable);
+    // paying fees in the mint would invoke this program again as its transfer hook
+    require!(
+        (authority.gift_card == 0 && authority.blocklist == 0) || fee_payout == FeePayout::BaseCoin,
+        CustomError::TransferHookCannotPayFeesInMint
+    );
+    require!(
+        authority.non_transferable == 0 || fee_payout == FeePayout::BaseCoin,
+        CustomError::NonTransferableCannotPayFeesInMint
+    );
+    // `distribute` only pays out the base coin
+    let (fee_split, _) =
+        Pubkey::find_program_address(&[b"fee_split", authority.mint.as_ref()], &crate::ID);
+    require!(
+        authority.fee_collector != fee_split || fee_payout == FeePayout::BaseCoin,
+        CustomError::FeeSplitCannotPayFeesInMint
+    );
+    authority.fee_payout = fee_payout as u8;
+    Ok(())
+}
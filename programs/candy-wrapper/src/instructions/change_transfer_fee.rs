@@ -13,7 +13,7 @@ use crate::{error::CustomError, state::Authority};
 pub struct TransferFeeCtx<'info> {
     #[account(
         mut,
-        constraint = payer.key() == authority.load()?.admin,
+        constraint = payer.key() == authority.load()?.admin @CustomError::IncorrectUpdateAuthority,
     )]
     pub payer: Signer<'info>,
     pub authority: AccountLoader<'info, Authority>,
@@ -37,6 +37,11 @@ pub fn change_transfer_fee_handler(
         ctx.accounts.authority.load()?.mutable == 1,
         CustomError::MintIsImmutable
     );
+    // non-transferable mints are created without the transfer fee extension
+    require!(
+        ctx.accounts.authority.load()?.non_transferable == 0,
+        CustomError::MintIsNonTransferable
+    );
     let mint_key = ctx.accounts.mint.key();
     let seeds: &[&[u8]] = &[
         b"authority",
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::{
+        spl_token_2022::extension::{
+            transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions,
+        },
+        spl_token_2022::state,
+        Token2022,
+    },
+    token_interface::{transfer_hook_update, Mint, TokenInterface, TransferHookUpdate},
+};
+
+use crate::{error::CustomError, state::Authority};
+
+#[derive(Accounts)]
+pub struct TransferHookCtx<'info> {
+    #[account(
+        mut,
+        constraint = payer.key() == authority.load()?.admin @CustomError::IncorrectUpdateAuthority,
+    )]
+    pub payer: Signer<'info>,
+    pub authority: AccountLoader<'info, Authority>,
+    #[account(
+        mut,
+        constraint = mint.key() == authority.load()?.mint @CustomError::IncorrectMint,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        address = Token2022::id()
+    )]
+    pub token_program_mint: Interface<'info, TokenInterface>,
+}
+
+pub fn change_transfer_hook_handler(
+    ctx: Context<TransferHookCtx>,
+    program_id: Option<Pubkey>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.authority.load()?.mutable == 1,
+        CustomError::MintIsImmutable
+    );
+
+    // the hook may have been created without one, or with an authority other than ours
+    {
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<state::Mint>::unpack(&mint_data)?;
+        let hook_authority = mint
+            .get_extension::<TransferHook>()
+            .ok()
+            .and_then(|transfer_hook| Option::<Pubkey>::from(transfer_hook.authority));
+        require!(
+            hook_authority == Some(ctx.accounts.authority.key()),
+            CustomError::IncorrectTransferHookAuthority
+        );
+    }
+
+    let mint_key = ctx.accounts.mint.key();
+    let seeds: &[&[u8]] = &[
+        b"authority",
+        mint_key.as_ref(),
+        &[ctx.accounts.authority.load()?.bump],
+    ];
+    let signer = &[seeds];
+
+    transfer_hook_update(
+        CpiContext::new(
+            ctx.accounts.token_program_mint.to_account_info(),
+            TransferHookUpdate {
+                token_program_id: ctx.accounts.token_program_mint.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        )
+        .with_signer(signer),
+        program_id,
+    )?;
+    Ok(())
+}
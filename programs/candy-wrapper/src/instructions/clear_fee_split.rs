@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, harvest_withheld_tokens_to_mint, CloseAccount, HarvestWithheldTokensToMint,
+    Mint, TokenAccount, TokenInterface,
+};
+
+use crate::{
+    error::CustomError,
+    instructions::distribute::pay_fee_split,
+    state::{Authority, FeeSplit},
+    utils::get_account_withheld_fee,
+};
+
+#[derive(Accounts)]
+pub struct ClearFeeSplitCtx<'info> {
+    #[account(
+        mut,
+        constraint = payer.key() == authority.load()?.admin @CustomError::IncorrectUpdateAuthority,
+    )]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"authority", mint.key().as_ref()],
+        bump = authority.load()?.bump,
+    )]
+    pub authority: AccountLoader<'info, Authority>,
+    #[account(
+        constraint = mint.key() == authority.load()?.mint @CustomError::IncorrectMint,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    // mutable to harvest the fees withheld in the split's token account
+    #[account(
+        mut,
+        constraint = base_coin.key() == authority.load()?.base_coin @CustomError::UnauthorizedBaseCoin,
+    )]
+    pub base_coin: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"fee_split", mint.key().as_ref()],
+        bump = fee_split.bump,
+        close = payer,
+    )]
+    pub fee_split: Box<Account<'info, FeeSplit>>,
+    #[account(
+        mut,
+        associated_token::mint = base_coin,
+        associated_token::authority = fee_split,
+        associated_token::token_program = token_program_base_coin,
+    )]
+    pub fee_split_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program_base_coin: Interface<'info, TokenInterface>,
+}
+
+// pays out what is left in the fee split, the last recipient taking the rounding remainder,
+// closes it and its token account and hands the fees over to `new_fee_collector`
+pub fn clear_fee_split_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClearFeeSplitCtx<'info>>,
+    new_fee_collector: Pubkey,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    {
+        let authority = accounts.authority.load()?;
+        require!(authority.mutable == 1, CustomError::MintIsImmutable);
+    }
+    require_keys_neq!(
+        new_fee_collector,
+        accounts.fee_split.key(),
+        CustomError::FeeCollectorIsFeeSplit
+    );
+
+    let fee_split_token_account = accounts.fee_split_base_coin_token_account.to_account_info();
+    pay_fee_split(
+        &accounts.fee_split,
+        &accounts.base_coin,
+        fee_split_token_account.clone(),
+        accounts.fee_split_base_coin_token_account.amount,
+        &accounts.token_program_base_coin,
+        ctx.remaining_accounts,
+        true,
+    )?;
+
+    // fees withheld in a token account stop it from closing, so they are harvested first
+    if get_account_withheld_fee(&fee_split_token_account)? > 0 {
+        harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                accounts.token_program_base_coin.to_account_info(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: accounts.token_program_base_coin.to_account_info(),
+                    mint: accounts.base_coin.to_account_info(),
+                },
+            ),
+            vec![fee_split_token_account.clone()],
+        )?;
+    }
+    let mint = accounts.mint.key();
+    let fee_split_seeds: &[&[u8]] = &[b"fee_split", mint.as_ref(), &[accounts.fee_split.bump]];
+    close_account(
+        CpiContext::new(
+            accounts.token_program_base_coin.to_account_info(),
+            CloseAccount {
+                account: fee_split_token_account,
+                destination: accounts.payer.to_account_info(),
+                authority: accounts.fee_split.to_account_info(),
+            },
+        )
+        .with_signer(&[fee_split_seeds]),
+    )?;
+
+    accounts.authority.load_mut()?.fee_collector = new_fee_collector;
+    Ok(())
+}
@@ -1,13 +1,34 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::Mint;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_2022::{
+        spl_token_2022::{
+            extension::{
+                mint_close_authority::MintCloseAuthority, BaseStateWithExtensions,
+                StateWithExtensions,
+            },
+            state,
+        },
+        Token2022,
+    },
+    token_interface::{
+        close_account, harvest_withheld_tokens_to_mint, CloseAccount, HarvestWithheldTokensToMint,
+        Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
 
-use crate::{error::CustomError, state::Authority};
+use crate::{
+    error::CustomError,
+    instructions::distribute::pay_fee_split,
+    state::{Authority, Compliance, FeeSplit, MerchantRegistry},
+    utils::{get_account_withheld_fee, transfer_checked_with_hook},
+};
 
 #[derive(Accounts)]
 pub struct CloseAccountCtx<'info> {
     #[account(
         mut,
-        constraint = payer.key() == authority.load()?.admin,
+        constraint = payer.key() == authority.load()?.admin @CustomError::IncorrectUpdateAuthority,
     )]
     pub payer: Signer<'info>,
     #[account(
@@ -19,11 +40,309 @@ pub struct CloseAccountCtx<'info> {
         mut,
         constraint = mint.key() == authority.load()?.mint @CustomError::IncorrectMint,
     )]
-    pub mint: InterfaceAccount<'info, Mint>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    // mutable to harvest the fees withheld in the reserves and the fee vault
+    #[account(
+        mut,
+        constraint = base_coin.key() == authority.load()?.base_coin @CustomError::UnauthorizedBaseCoin,
+    )]
+    pub base_coin: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &authority.key(),
+            &base_coin.key(),
+            &token_program_base_coin.key(),
+        ),
+    )]
+    /// CHECK: Only exists once the mint has been issued
+    pub authority_base_coin_token_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"fee_vault", mint.key().as_ref()],
+        bump = authority.load()?.fee_vault_bump,
+        token::mint = base_coin,
+        token::authority = authority,
+        token::token_program = token_program_base_coin,
+    )]
+    pub fee_vault_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // the associated token account, so fees paid to a fee split always reach `distribute`
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &authority.load()?.fee_collector,
+            &base_coin.key(),
+            &token_program_base_coin.key(),
+        ) @CustomError::IncorrectFeeCollector,
+        token::mint = base_coin,
+        token::token_program = token_program_base_coin,
+    )]
+    pub fee_collector_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &authority.key(),
+            &mint.key(),
+            &token_program_mint.key(),
+        ),
+    )]
+    /// CHECK: Only exists once fees have been withdrawn
+    pub authority_mint_token_account: UncheckedAccount<'info>,
+    #[account(
+        address = Token2022::id()
+    )]
+    pub token_program_mint: Interface<'info, TokenInterface>,
+    pub token_program_base_coin: Interface<'info, TokenInterface>,
+    // the accounts below are closed if the mint has them, and must be passed whenever the
+    // mint's `Authority` says it does
+    #[account(
+        mut,
+        seeds = [b"fee_split", mint.key().as_ref()],
+        bump = fee_split.bump,
+        close = payer,
+    )]
+    pub fee_split: Option<Box<Account<'info, FeeSplit>>>,
+    #[account(
+        mut,
+        seeds = [b"merchant_registry", mint.key().as_ref()],
+        bump = merchant_registry.bump,
+        close = payer,
+    )]
+    pub merchant_registry: Option<Box<Account<'info, MerchantRegistry>>>,
+    #[account(
+        mut,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: The `ExtraAccountMetaList` of gift cards and blocklists
+    pub extra_account_meta_list: Option<UncheckedAccount<'info>>,
+    // created by anyone, or by the admin at any time, so always passed and closed if they exist
+    #[account(
+        mut,
+        seeds = [b"reserve_attestation", mint.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: A `ReserveAttestation`, if owned by this program
+    pub reserve_attestation: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"compliance", mint.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: A `Compliance`, if owned by this program
+    pub compliance: UncheckedAccount<'info>,
 }
 
-pub fn close_account_handler(ctx: Context<CloseAccountCtx>) -> Result<()> {
+// sweeps the dust left in the reserves to the fee collector, pays out what is left for a fee
+// split, then closes the token accounts, the mint and the program accounts of the mint,
+// returning their rent to the admin
+pub fn close_account_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseAccountCtx<'info>>,
+) -> Result<()> {
     require!(ctx.accounts.mint.supply == 0, CustomError::MintIsNotZero);
+    // fees must be withdrawn and the protocol's share claimed first
+    require!(
+        ctx.accounts.fee_vault_base_coin_token_account.amount == 0,
+        CustomError::FeeVaultIsNotEmpty
+    );
+
+    let (mint, bump, fee_collector, gift_card, blocklist) = {
+        let authority = ctx.accounts.authority.load()?;
+        (
+            authority.mint,
+            authority.bump,
+            authority.fee_collector,
+            authority.gift_card == 1,
+            authority.blocklist == 1,
+        )
+    };
+    let (fee_split, _) = Pubkey::find_program_address(&[b"fee_split", mint.as_ref()], &crate::ID);
+    require!(
+        ctx.accounts.fee_split.is_some() || fee_collector != fee_split,
+        CustomError::MissingClosableAccount
+    );
+    require!(
+        ctx.accounts.merchant_registry.is_some() || !gift_card,
+        CustomError::MissingClosableAccount
+    );
+    require!(
+        ctx.accounts.extra_account_meta_list.is_some() || !(gift_card || blocklist),
+        CustomError::MissingClosableAccount
+    );
+    let seeds: &[&[u8]] = &[b"authority", mint.as_ref(), &[bump]];
+    let signer = &[seeds];
+
+    let accounts = &ctx.accounts;
+    let authority = accounts.authority.to_account_info();
+    let reserves = accounts.authority_base_coin_token_account.to_account_info();
+    if *reserves.owner == accounts.token_program_base_coin.key() {
+        let amount = token_account_amount(&reserves)?;
+        if amount > 0 {
+            transfer_checked_with_hook(
+                CpiContext::new(
+                    accounts.token_program_base_coin.to_account_info(),
+                    TransferChecked {
+                        from: reserves.clone(),
+                        mint: accounts.base_coin.to_account_info(),
+                        to: accounts
+                            .fee_collector_base_coin_token_account
+                            .to_account_info(),
+                        authority: authority.clone(),
+                    },
+                )
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec())
+                .with_signer(signer),
+                amount,
+                accounts.base_coin.decimals,
+            )?;
+        }
+        close_base_coin_token_account(accounts, reserves, authority.clone(), signer)?;
+    }
+    close_base_coin_token_account(
+        accounts,
+        accounts.fee_vault_base_coin_token_account.to_account_info(),
+        authority.clone(),
+        signer,
+    )?;
+
+    // a fee split is the fee collector until cleared, so its token account received the dust above
+    if let Some(fee_split) = &accounts.fee_split {
+        require_keys_eq!(
+            fee_collector,
+            fee_split.key(),
+            CustomError::IncorrectFeeCollector
+        );
+        let fee_split_token_account = accounts
+            .fee_collector_base_coin_token_account
+            .to_account_info();
+        pay_fee_split(
+            fee_split,
+            &accounts.base_coin,
+            fee_split_token_account.clone(),
+            token_account_amount(&fee_split_token_account)?,
+            &accounts.token_program_base_coin,
+            ctx.remaining_accounts,
+            true,
+        )?;
+        let fee_split_seeds: &[&[u8]] = &[b"fee_split", mint.as_ref(), &[fee_split.bump]];
+        close_base_coin_token_account(
+            accounts,
+            fee_split_token_account,
+            fee_split.to_account_info(),
+            &[fee_split_seeds],
+        )?;
+    }
+
+    let authority_mint_token_account = accounts.authority_mint_token_account.to_account_info();
+    if *authority_mint_token_account.owner == accounts.token_program_mint.key() {
+        close_token_account(
+            &accounts.token_program_mint,
+            authority_mint_token_account,
+            accounts,
+            authority.clone(),
+            signer,
+        )?;
+    }
+
+    // mints created before the close authority was introduced stay open
+    if is_mint_closable(&accounts.mint.to_account_info(), &accounts.authority.key())? {
+        close_token_account(
+            &accounts.token_program_mint,
+            accounts.mint.to_account_info(),
+            accounts,
+            authority,
+            signer,
+        )?;
+    }
 
+    // not passed as Anchor accounts, so closed by hand
+    if let Some(extra_account_meta_list) = &accounts.extra_account_meta_list {
+        close_program_account(&extra_account_meta_list.to_account_info(), accounts)?;
+    }
+    let reserve_attestation = accounts.reserve_attestation.to_account_info();
+    if *reserve_attestation.owner == crate::ID {
+        close_program_account(&reserve_attestation, accounts)?;
+    }
+    let compliance = accounts.compliance.to_account_info();
+    if *compliance.owner == crate::ID {
+        // blocklist entries are only closed by unblocking their wallet
+        let blocked = Compliance::try_deserialize(&mut &compliance.try_borrow_data()?[..])?.blocked;
+        require_eq!(blocked, 0, CustomError::WalletsAreStillBlocked);
+        close_program_account(&compliance, accounts)?;
+    }
+
+    Ok(())
+}
+
+fn close_program_account(info: &AccountInfo, accounts: &CloseAccountCtx) -> Result<()> {
+    let payer = accounts.payer.to_account_info();
+    **payer.lamports.borrow_mut() = payer
+        .lamports()
+        .checked_add(info.lamports())
+        .ok_or(CustomError::MathOverflow)?;
+    **info.lamports.borrow_mut() = 0;
+    info.assign(&System::id());
+    info.realloc(0, false)?;
     Ok(())
 }
+
+fn token_account_amount(token_account: &AccountInfo) -> Result<u64> {
+    Ok(TokenAccount::try_deserialize(&mut &token_account.try_borrow_data()?[..])?.amount)
+}
+
+// fees withheld in a token account stop it from closing, so they are harvested first
+fn close_base_coin_token_account<'info>(
+    accounts: &CloseAccountCtx<'info>,
+    token_account: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    if get_account_withheld_fee(&token_account)? > 0 {
+        harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                accounts.token_program_base_coin.to_account_info(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: accounts.token_program_base_coin.to_account_info(),
+                    mint: accounts.base_coin.to_account_info(),
+                },
+            ),
+            vec![token_account.clone()],
+        )?;
+    }
+    close_token_account(
+        &accounts.token_program_base_coin,
+        token_account,
+        accounts,
+        authority,
+        signer,
+    )
+}
+
+fn close_token_account<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    account: AccountInfo<'info>,
+    accounts: &CloseAccountCtx<'info>,
+    authority: AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    close_account(
+        CpiContext::new(
+            token_program.to_account_info(),
+            CloseAccount {
+                account,
+                destination: accounts.payer.to_account_info(),
+                authority,
+            },
+        )
+        .with_signer(signer),
+    )
+}
+
+fn is_mint_closable(mint_info: &AccountInfo, authority: &Pubkey) -> Result<bool> {
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<state::Mint>::unpack(&mint_data)?;
+    let Ok(extension) = mint.get_extension::<MintCloseAuthority>() else {
+        return Ok(false);
+    };
+    Ok(Option::<Pubkey>::from(extension.close_authority) == Some(*authority))
+}
@@ -1,21 +1,39 @@
 use anchor_lang::{
     prelude::*,
-    system_program::{self, Transfer},
+    system_program::{self, Allocate, Assign, CreateAccount, Transfer},
 };
 use anchor_spl::{
     associated_token::AssociatedToken,
+    token_2022::spl_token_2022::{
+        extension::ExtensionType,
+        state::{self, AccountState},
+    },
     token_2022::Token2022,
     token_interface::{
-        initialize_mint, metadata_pointer_initialize, transfer_fee_initialize, InitializeMint,
-        MetadataPointerInitialize, Mint, TokenAccount, TokenInterface, TransferFeeInitialize,
+        default_account_state_initialize, initialize_mint, metadata_pointer_initialize,
+        mint_close_authority_initialize, non_transferable_mint_initialize, transfer_fee_initialize,
+        transfer_hook_initialize, DefaultAccountStateInitialize, InitializeMint,
+        MetadataPointerInitialize, Mint, MintCloseAuthorityInitialize,
+        NonTransferableMintInitialize, TokenAccount, TokenInterface, TransferFeeInitialize,
+        TransferHookInitialize,
     },
 };
+use spl_tlv_account_resolution::{
+    account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
+};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
 
 use crate::{
     error::CustomError,
-    state::{Authority, AUTHORITY_SPACE, PROTOCOL_WALLET},
+    state::{
+        Authority, MerchantRegistry, AUTHORITY_SPACE, MERCHANT_REGISTRY_SPACE, PROTOCOL_WALLET,
+    },
 };
 
+// the authority, the merchant registry and the blocklist entries of the source's and the
+// destination's owner
+pub const EXTRA_ACCOUNT_METAS_LEN: usize = 4;
+
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct CreateMintArgs {
     pub admin: Pubkey,
@@ -25,6 +43,25 @@ pub struct CreateMintArgs {
     pub redemption_fee_basis_pts: u16,
     pub fee_collector: Pubkey,
     pub transfer_fee_args: TransferFeeArgs,
+    pub transfer_hook_args: Option<TransferHookArgs>,
+    /// Restricts transfers to those to or from a wallet in the mint's merchant registry, through
+    /// a transfer hook run by this program. Cannot be combined with `transfer_hook_args`.
+    pub gift_card: bool,
+    /// Stops wallets blocked by the compliance officer from transferring, through the same
+    /// transfer hook. Issuance and redemption check the blocklist regardless.
+    pub blocklist: bool,
+    /// Makes the `Authority` account the freeze authority, so the compliance officer can freeze
+    /// and thaw token accounts.
+    pub freezable: bool,
+    /// Creates token accounts frozen, so only those thawed by the compliance officer can hold
+    /// the token. Requires `freezable`.
+    pub default_frozen: bool,
+    /// Stops holders from transferring the token, which they can still redeem. Such mints
+    /// charge no transfer fees, so `transfer_fee_args` must be zero.
+    pub non_transferable: bool,
+    /// Creates the mint at the address derived from the payer and this seed. Without a seed,
+    /// the mint is a fresh keypair signing the transaction.
+    pub mint_seed: Option<[u8; 32]>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -33,17 +70,22 @@ pub struct TransferFeeArgs {
     pub max_fee: u64,
 }
 
+/// Program invoked on every transfer of the mint. The admin can only change it through
+/// `change_transfer_hook` if `authority` is the mint's `Authority` account.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct TransferHookArgs {
+    pub program_id: Pubkey,
+    pub authority: Pubkey,
+}
+
 #[derive(Accounts)]
 #[instruction(args: CreateMintArgs)]
 pub struct CreateMintCtx<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
-    #[account(
-        mut,
-        owner = token_program_mint.key(),
-    )]
-    /// CHECK: Mint to be created
-    pub mint: AccountInfo<'info>,
+    #[account(mut)]
+    /// CHECK: Mint to be created, checked against `args.mint_seed` by the handler
+    pub mint: UncheckedAccount<'info>,
     #[account(
         init,
         space = AUTHORITY_SPACE,
@@ -56,7 +98,9 @@ pub struct CreateMintCtx<'info> {
         constraint = base_coin.key() == args.base_coin @CustomError::UnauthorizedBaseCoin,
     )]
     pub base_coin: InterfaceAccount<'info, Mint>,
+    // writable to receive the creation fee
     #[account(
+        mut,
         address = PROTOCOL_WALLET,
     )]
     /// CHECK:
@@ -69,6 +113,33 @@ pub struct CreateMintCtx<'info> {
         associated_token::authority = protocol_wallet,
     )]
     pub protocol_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"fee_vault", mint.key().as_ref()],
+        bump,
+        token::mint = base_coin,
+        token::authority = authority,
+        token::token_program = token_program_base_coin,
+    )]
+    pub fee_vault_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = payer,
+        space = ExtraAccountMetaList::size_of(EXTRA_ACCOUNT_METAS_LEN)?,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: Written as an `ExtraAccountMetaList`, only for gift cards and blocklists
+    pub extra_account_meta_list: Option<UncheckedAccount<'info>>,
+    #[account(
+        init,
+        payer = payer,
+        space = MERCHANT_REGISTRY_SPACE,
+        seeds = [b"merchant_registry", mint.key().as_ref()],
+        bump,
+    )]
+    pub merchant_registry: Option<Box<Account<'info, MerchantRegistry>>>,
     #[account(
         address = Token2022::id(),
     )]
@@ -79,6 +150,161 @@ pub struct CreateMintCtx<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+/// Extensions `create_mint` initializes for `args`, which size the mint account.
+pub fn mint_extensions(args: &CreateMintArgs) -> Vec<ExtensionType> {
+    let mut extensions = vec![
+        if args.non_transferable {
+            ExtensionType::NonTransferable
+        } else {
+            ExtensionType::TransferFeeConfig
+        },
+        ExtensionType::MetadataPointer,
+        ExtensionType::MintCloseAuthority,
+    ];
+    if args.transfer_hook_args.is_some() || args.gift_card || args.blocklist {
+        extensions.push(ExtensionType::TransferHook);
+    }
+    if args.default_frozen {
+        extensions.push(ExtensionType::DefaultAccountState);
+    }
+    extensions
+}
+
+// allocates the mint for its extensions and assigns it to the token program, signing for it
+// if it is derived from a seed
+fn allocate_mint(ctx: &Context<CreateMintCtx>, args: &CreateMintArgs) -> Result<()> {
+    let payer = ctx.accounts.payer.key();
+    let bump;
+    let seeds: &[&[u8]] = match &args.mint_seed {
+        None => {
+            require!(ctx.accounts.mint.is_signer, CustomError::MintIsNotSigner);
+            &[]
+        }
+        Some(mint_seed) => {
+            let mint;
+            (mint, bump) =
+                Pubkey::find_program_address(&[b"mint", payer.as_ref(), mint_seed], &crate::ID);
+            require_keys_eq!(
+                ctx.accounts.mint.key(),
+                mint,
+                CustomError::IncorrectMintSeed
+            );
+            &[
+                b"mint",
+                payer.as_ref(),
+                mint_seed,
+                std::slice::from_ref(&bump),
+            ]
+        }
+    };
+    let signer: &[&[&[u8]]] = if seeds.is_empty() { &[] } else { &[seeds] };
+
+    let space = ExtensionType::try_calculate_account_len::<state::Mint>(&mint_extensions(args))?;
+    let lamports = ctx.accounts.rent.minimum_balance(space);
+    let token_program = ctx.accounts.token_program_mint.key();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let mint = ctx.accounts.mint.to_account_info();
+
+    let current_lamports = mint.lamports();
+    if current_lamports == 0 {
+        return system_program::create_account(
+            CpiContext::new(
+                system_program,
+                CreateAccount {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: mint,
+                },
+            )
+            .with_signer(signer),
+            lamports,
+            space as u64,
+            &token_program,
+        );
+    }
+
+    // anyone can send lamports to the address first, which would make creating it fail, so top
+    // it up to rent exemption and allocate it instead, as Anchor's `init` does
+    let top_up = lamports.saturating_sub(current_lamports);
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: mint.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new(
+            system_program.clone(),
+            Allocate {
+                account_to_allocate: mint.clone(),
+            },
+        )
+        .with_signer(signer),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new(
+            system_program,
+            Assign {
+                account_to_assign: mint,
+            },
+        )
+        .with_signer(signer),
+        &token_program,
+    )
+}
+
+fn extra_account_metas() -> Result<[ExtraAccountMeta; EXTRA_ACCOUNT_METAS_LEN]> {
+    // accounts of the transfer are source, mint, destination and owner, in that order, and the
+    // owner of a token account is stored at offset 32
+    let blocklist_entry = |account_index| {
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"blocklist".to_vec(),
+                },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountData {
+                    account_index,
+                    data_index: 32,
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )
+    };
+    Ok([
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"authority".to_vec(),
+                },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            false,
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"merchant_registry".to_vec(),
+                },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            false,
+        )?,
+        blocklist_entry(0)?,
+        blocklist_entry(2)?,
+    ])
+}
+
 pub fn create_mint_handler(ctx: Context<CreateMintCtx>, args: CreateMintArgs) -> Result<()> {
     require!(
         args.issuance_fee_basis_pts <= 100,
@@ -92,8 +318,24 @@ pub fn create_mint_handler(ctx: Context<CreateMintCtx>, args: CreateMintArgs) ->
         args.mint_to_base_ratio > 0,
         CustomError::MintRatioCannotBeZero
     );
+    require!(
+        !(args.gift_card || args.blocklist) || args.transfer_hook_args.is_none(),
+        CustomError::TransferHookTakenByProgram
+    );
+    require!(
+        args.freezable || !args.default_frozen,
+        CustomError::DefaultFrozenRequiresFreezable
+    );
+    require!(
+        !args.non_transferable
+            || (args.transfer_fee_args.fee_basis_pts == 0 && args.transfer_fee_args.max_fee == 0),
+        CustomError::NonTransferableCannotChargeTransferFees
+    );
+    allocate_mint(&ctx, &args)?;
+
     let authority = &mut ctx.accounts.authority.load_init()?;
     authority.bump = ctx.bumps.authority;
+    authority.fee_vault_bump = ctx.bumps.fee_vault_base_coin_token_account;
     authority.base_coin = args.base_coin;
     authority.mint = ctx.accounts.mint.key();
     authority.mint_to_base_ratio = args.mint_to_base_ratio;
@@ -102,21 +344,34 @@ pub fn create_mint_handler(ctx: Context<CreateMintCtx>, args: CreateMintArgs) ->
     authority.issuance_fee_basis_pts = args.issuance_fee_basis_pts;
     authority.redemption_fee_basis_pts = args.redemption_fee_basis_pts;
     authority.mutable = 1;
+    authority.gift_card = args.gift_card as u8;
+    authority.blocklist = args.blocklist as u8;
+    authority.non_transferable = args.non_transferable as u8;
 
-    // initialize transfer fee
-    transfer_fee_initialize(
-        CpiContext::new(
+    // initialize transfer fee, or make the mint non-transferable instead
+    if args.non_transferable {
+        non_transferable_mint_initialize(CpiContext::new(
             ctx.accounts.token_program_mint.to_account_info(),
-            TransferFeeInitialize {
+            NonTransferableMintInitialize {
                 token_program_id: ctx.accounts.token_program_mint.to_account_info(),
                 mint: ctx.accounts.mint.to_account_info(),
             },
-        ),
-        Some(&ctx.accounts.authority.key()),
-        Some(&ctx.accounts.authority.key()),
-        args.transfer_fee_args.fee_basis_pts,
-        args.transfer_fee_args.max_fee,
-    )?;
+        ))?;
+    } else {
+        transfer_fee_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program_mint.to_account_info(),
+                TransferFeeInitialize {
+                    token_program_id: ctx.accounts.token_program_mint.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            Some(&ctx.accounts.authority.key()),
+            Some(&ctx.accounts.authority.key()),
+            args.transfer_fee_args.fee_basis_pts,
+            args.transfer_fee_args.max_fee,
+        )?;
+    }
 
     // initialize mint metadata pointer
     metadata_pointer_initialize(
@@ -131,6 +386,77 @@ pub fn create_mint_handler(ctx: Context<CreateMintCtx>, args: CreateMintArgs) ->
         Some(ctx.accounts.mint.key()),
     )?;
 
+    // initialize mint close authority, so `close_account` can reclaim the mint's rent
+    mint_close_authority_initialize(
+        CpiContext::new(
+            ctx.accounts.token_program_mint.to_account_info(),
+            MintCloseAuthorityInitialize {
+                token_program_id: ctx.accounts.token_program_mint.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+            },
+        ),
+        Some(&ctx.accounts.authority.key()),
+    )?;
+
+    // initialize transfer hook
+    if let Some(transfer_hook_args) = args.transfer_hook_args {
+        transfer_hook_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program_mint.to_account_info(),
+                TransferHookInitialize {
+                    token_program_id: ctx.accounts.token_program_mint.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            Some(transfer_hook_args.authority),
+            Some(transfer_hook_args.program_id),
+        )?;
+    }
+
+    // initialize this program as transfer hook, without an authority so it stays for good
+    if args.gift_card || args.blocklist {
+        let Some(extra_account_meta_list) = &ctx.accounts.extra_account_meta_list else {
+            return err!(CustomError::MissingTransferHookAccounts);
+        };
+        let mut data = extra_account_meta_list.try_borrow_mut_data()?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas()?)?;
+
+        transfer_hook_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program_mint.to_account_info(),
+                TransferHookInitialize {
+                    token_program_id: ctx.accounts.token_program_mint.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            None,
+            Some(crate::ID),
+        )?;
+    }
+
+    // initialize gift card
+    if args.gift_card {
+        let Some(merchant_registry) = &mut ctx.accounts.merchant_registry else {
+            return err!(CustomError::MissingTransferHookAccounts);
+        };
+        merchant_registry.bump = ctx.bumps.merchant_registry.unwrap();
+        merchant_registry.mint = ctx.accounts.mint.key();
+    }
+
+    // initialize default account state
+    if args.default_frozen {
+        default_account_state_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program_mint.to_account_info(),
+                DefaultAccountStateInitialize {
+                    token_program_id: ctx.accounts.token_program_mint.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            &AccountState::Frozen,
+        )?;
+    }
+
     // intialize mint
     initialize_mint(
         CpiContext::new(
@@ -142,7 +468,9 @@ pub fn create_mint_handler(ctx: Context<CreateMintCtx>, args: CreateMintArgs) ->
         ),
         ctx.accounts.base_coin.decimals,
         &ctx.accounts.authority.key(),
-        None,
+        args.freezable
+            .then(|| ctx.accounts.authority.key())
+            .as_ref(),
     )?;
 
     system_program::transfer(
@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::CustomError,
+    state::FeeSplit,
+    utils::{calculate_share, transfer_checked_with_hook},
+};
+
+#[derive(Accounts)]
+pub struct DistributeCtx<'info> {
+    #[account(
+        seeds = [b"fee_split", fee_split.mint.as_ref()],
+        bump = fee_split.bump,
+    )]
+    pub fee_split: Box<Account<'info, FeeSplit>>,
+    #[account(
+        constraint = base_coin.key() == fee_split.base_coin @CustomError::UnauthorizedBaseCoin,
+    )]
+    pub base_coin: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = base_coin,
+        associated_token::authority = fee_split,
+        associated_token::token_program = token_program_base_coin,
+    )]
+    pub fee_split_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program_base_coin: Interface<'info, TokenInterface>,
+}
+
+pub fn distribute_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeCtx<'info>>,
+) -> Result<()> {
+    pay_fee_split(
+        &ctx.accounts.fee_split,
+        &ctx.accounts.base_coin,
+        ctx.accounts
+            .fee_split_base_coin_token_account
+            .to_account_info(),
+        ctx.accounts.fee_split_base_coin_token_account.amount,
+        &ctx.accounts.token_program_base_coin,
+        ctx.remaining_accounts,
+        false,
+    )
+}
+
+// pays each recipient its share of `balance`, or, with `pay_remainder`, the last one whatever
+// rounding leaves, emptying the split's token account
+pub fn pay_fee_split<'info>(
+    fee_split: &Account<'info, FeeSplit>,
+    base_coin: &InterfaceAccount<'info, Mint>,
+    fee_split_base_coin_token_account: AccountInfo<'info>,
+    balance: u64,
+    token_program_base_coin: &Interface<'info, TokenInterface>,
+    remaining_accounts: &[AccountInfo<'info>],
+    pay_remainder: bool,
+) -> Result<()> {
+    // the recipients' token accounts, followed by the accounts needed by a base coin transfer hook
+    require!(
+        remaining_accounts.len() >= fee_split.recipients.len(),
+        CustomError::IncorrectFeeSplitRecipient
+    );
+
+    let seeds: &[&[u8]] = &[b"fee_split", fee_split.mint.as_ref(), &[fee_split.bump]];
+    let signer = &[seeds];
+
+    let mut paid: u64 = 0;
+    for (index, (recipient, recipient_token_account)) in fee_split
+        .recipients
+        .iter()
+        .zip(remaining_accounts.iter())
+        .enumerate()
+    {
+        require_keys_eq!(
+            recipient_token_account.key(),
+            get_associated_token_address_with_program_id(
+                &recipient.wallet,
+                &fee_split.base_coin,
+                &token_program_base_coin.key(),
+            ),
+            CustomError::IncorrectFeeSplitRecipient
+        );
+
+        let share = if pay_remainder && index == fee_split.recipients.len() - 1 {
+            balance.checked_sub(paid).ok_or(CustomError::MathOverflow)?
+        } else {
+            calculate_share(balance, recipient.basis_pts)?
+        };
+        if share == 0 {
+            continue;
+        }
+        paid = paid.checked_add(share).ok_or(CustomError::MathOverflow)?;
+
+        transfer_checked_with_hook(
+            CpiContext::new(
+                token_program_base_coin.to_account_info(),
+                TransferChecked {
+                    from: fee_split_base_coin_token_account.clone(),
+                    mint: base_coin.to_account_info(),
+                    to: recipient_token_account.to_account_info(),
+                    authority: fee_split.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec())
+            .with_signer(signer),
+            share,
+            base_coin.decimals,
+        )?;
+    }
+
+    Ok(())
+}
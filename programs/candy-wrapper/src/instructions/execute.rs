@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::{
+    error::CustomError,
+    state::{Authority, MerchantRegistry},
+};
+
+#[derive(Accounts)]
+pub struct ExecuteCtx<'info> {
+    #[account(
+        token::mint = mint,
+    )]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        token::mint = mint,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Owner or delegate of the source, checked by the token program
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: Address checked by seeds
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"authority", mint.key().as_ref()],
+        bump = authority.load()?.bump,
+    )]
+    pub authority: AccountLoader<'info, Authority>,
+    #[account(
+        seeds = [b"merchant_registry", mint.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: Only exists for gift cards
+    pub merchant_registry: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"blocklist", mint.key().as_ref(), source.owner.as_ref()],
+        bump,
+    )]
+    /// CHECK: Only exists if the source's owner is blocked
+    pub source_blocklist_entry: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"blocklist", mint.key().as_ref(), destination.owner.as_ref()],
+        bump,
+    )]
+    /// CHECK: Only exists if the destination's owner is blocked
+    pub destination_blocklist_entry: UncheckedAccount<'info>,
+}
+
+pub fn execute_handler(ctx: Context<ExecuteCtx>, _amount: u64) -> Result<()> {
+    require!(
+        ctx.accounts.source_blocklist_entry.owner != &crate::ID
+            && ctx.accounts.destination_blocklist_entry.owner != &crate::ID,
+        CustomError::WalletIsBlocked
+    );
+
+    if ctx.accounts.authority.load()?.gift_card == 1 {
+        let data = ctx.accounts.merchant_registry.try_borrow_data()?;
+        let merchants = MerchantRegistry::try_deserialize(&mut &data[..])?.merchants;
+        require!(
+            merchants.contains(&ctx.accounts.source.owner)
+                || merchants.contains(&ctx.accounts.destination.owner),
+            CustomError::TransferRequiresMerchant
+        );
+    }
+    Ok(())
+}
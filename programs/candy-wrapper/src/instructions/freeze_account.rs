@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::Token2022,
+    token_interface::{freeze_account, FreezeAccount, Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::CustomError,
+    events::AccountFrozen,
+    state::{Authority, Compliance},
+};
+
+#[derive(Accounts)]
+pub struct FreezeCtx<'info> {
+    #[account(
+        constraint = officer.key() == compliance.officer @CustomError::IncorrectComplianceOfficer,
+    )]
+    pub officer: Signer<'info>,
+    #[account(
+        constraint = mint.key() == authority.load()?.mint @CustomError::IncorrectMint,
+        constraint = mint.freeze_authority == Some(authority.key()).into() @CustomError::MintIsNotFreezable,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [b"authority", mint.key().as_ref()],
+        bump = authority.load()?.bump,
+    )]
+    pub authority: AccountLoader<'info, Authority>,
+    #[account(
+        seeds = [b"compliance", mint.key().as_ref()],
+        bump = compliance.bump,
+    )]
+    pub compliance: Account<'info, Compliance>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program_mint,
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        address = Token2022::id()
+    )]
+    pub token_program_mint: Interface<'info, TokenInterface>,
+}
+
+pub fn freeze_account_handler(ctx: Context<FreezeCtx>) -> Result<()> {
+    let mint_key = ctx.accounts.mint.key();
+    let seeds: &[&[u8]] = &[
+        b"authority",
+        mint_key.as_ref(),
+        &[ctx.accounts.authority.load()?.bump],
+    ];
+    let signer = &[seeds];
+
+    freeze_account(
+        CpiContext::new(
+            ctx.accounts.token_program_mint.to_account_info(),
+            FreezeAccount {
+                account: ctx.accounts.token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        )
+        .with_signer(signer),
+    )?;
+
+    emit!(AccountFrozen {
+        mint: mint_key,
+        token_account: ctx.accounts.token_account.key(),
+        officer: ctx.accounts.officer.key(),
+    });
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::instruction::withdraw_withheld_tokens_from_accounts;
+use solana_program::program::invoke_signed;
+
+use crate::{
+    error::CustomError,
+    instructions::withdraw_fees::*,
+    utils::{get_account_withheld_fee, is_token_account_of},
+};
+
+pub fn harvest_and_withdraw_fees_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawFeesCtx<'info>>,
+) -> Result<()> {
+    thaw_authority_mint_token_account(&ctx)?;
+
+    // remaining accounts other than token accounts of the mint are for the transfer hooks of
+    // the base coin and the mint
+    let mint_key = ctx.accounts.mint.key();
+    let holder_token_accounts: Vec<AccountInfo<'info>> = ctx
+        .remaining_accounts
+        .iter()
+        .filter(|account| is_token_account_of(account, &mint_key))
+        .cloned()
+        .collect();
+
+    let mut harvested_amount: u64 = 0;
+    for holder_token_account in holder_token_accounts.iter() {
+        harvested_amount = harvested_amount
+            .checked_add(get_account_withheld_fee(holder_token_account)?)
+            .ok_or(CustomError::MathOverflow)?;
+    }
+
+    if !holder_token_accounts.is_empty() {
+        let seeds: &[&[u8]] = &[
+            b"authority",
+            mint_key.as_ref(),
+            &[ctx.accounts.authority.load()?.bump],
+        ];
+        let signer = &[seeds];
+
+        let sources: Vec<&Pubkey> = holder_token_accounts.iter().map(|a| a.key).collect();
+        let ix = withdraw_withheld_tokens_from_accounts(
+            ctx.accounts.token_program_mint.key,
+            &ctx.accounts.mint.key(),
+            &ctx.accounts.authority_mint_token_account.key(),
+            &ctx.accounts.authority.key(),
+            &[],
+            &sources,
+        )?;
+        let mut account_infos = vec![
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.authority_mint_token_account.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+        ];
+        account_infos.extend(holder_token_accounts);
+        invoke_signed(&ix, &account_infos, signer)?;
+    }
+
+    withdraw_and_convert_fees(ctx, harvested_amount)
+}
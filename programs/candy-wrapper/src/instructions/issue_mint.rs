@@ -2,15 +2,16 @@ use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_2022::Token2022,
-    token_interface::{
-        mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
-    },
+    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
 };
 
 use crate::{
     error::CustomError,
-    state::{Authority, ProtocolFeeConfig, PROTOCOL_WALLET},
-    utils::{calculate_fee, calculate_mint_amount},
+    state::Authority,
+    utils::{
+        backing, calculate_fee, calculate_mint_amount, get_post_fee_amount, require_solvent,
+        transfer_checked_with_hook,
+    },
 };
 #[derive(Accounts)]
 pub struct IssueMintCtx<'info> {
@@ -48,31 +49,28 @@ pub struct IssueMintCtx<'info> {
         associated_token::token_program = token_program_base_coin
     )]
     pub authority_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // read only, so issuance never contends with other transactions on the authority
     #[account(
-        mut,
         seeds = [b"authority", mint.key().as_ref()],
         bump = authority.load()?.bump,
     )]
     pub authority: AccountLoader<'info, Authority>,
     #[account(
         mut,
+        seeds = [b"fee_vault", mint.key().as_ref()],
+        bump = authority.load()?.fee_vault_bump,
         token::mint = base_coin,
+        token::authority = authority,
         token::token_program = token_program_base_coin,
-        constraint = fee_collector_base_coin_token_account.owner == authority.load()?.fee_collector @CustomError::IncorrectFeeCollector,
-    )]
-    pub fee_collector_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
-    #[account(
-        seeds = [b"config", PROTOCOL_WALLET.as_ref()],
-        bump = protocol_fee_config.bump,
     )]
-    pub protocol_fee_config: Box<Account<'info, ProtocolFeeConfig>>,
+    pub fee_vault_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
-        mut,
-        token::mint = base_coin,
-        token::token_program = token_program_base_coin,
-        constraint = protocol_base_coin_token_account.owner == PROTOCOL_WALLET,
+        seeds = [b"blocklist", mint.key().as_ref(), payer.key().as_ref()],
+        bump,
+        constraint = *payer_blocklist_entry.owner != crate::ID @CustomError::WalletIsBlocked,
     )]
-    pub protocol_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: Only exists if the payer is blocked
+    pub payer_blocklist_entry: UncheckedAccount<'info>,
     #[account(
         address = Token2022::id()
     )]
@@ -83,7 +81,7 @@ pub struct IssueMintCtx<'info> {
 }
 
 pub fn issue_mint_handler<'info>(
-    ctx: Context<'_, '_, '_, 'info, IssueMintCtx<'info>>,
+    mut ctx: Context<'_, '_, '_, 'info, IssueMintCtx<'info>>,
     amount: u64,
 ) -> Result<()> {
     require!(
@@ -98,19 +96,27 @@ pub fn issue_mint_handler<'info>(
         &[ctx.accounts.authority.load()?.bump],
     ];
     let signer = &[seeds];
+    let before = backing(
+        &ctx.accounts.authority_base_coin_token_account,
+        &ctx.accounts.mint,
+    );
 
     let fee = calculate_fee(
         amount,
         ctx.accounts.authority.load()?.issuance_fee_basis_pts,
-    );
+    )?;
     let amount_after_fee = amount.saturating_sub(fee);
+    // a base coin charging transfer fees delivers less than is sent, so issue for what arrives
+    let deposited_amount =
+        get_post_fee_amount(&ctx.accounts.base_coin.to_account_info(), amount_after_fee)?;
 
     let mint_amount = calculate_mint_amount(
-        amount_after_fee,
+        deposited_amount,
         ctx.accounts.authority.load()?.mint_to_base_ratio,
         ctx.accounts.authority_base_coin_token_account.amount,
         ctx.accounts.mint.supply,
-    );
+    )?;
+    require!(mint_amount > 0, CustomError::ZeroOutput);
 
     mint_to(
         CpiContext::new(
@@ -125,10 +131,9 @@ pub fn issue_mint_handler<'info>(
         mint_amount,
     )?;
 
+    // the fee is split between the fee collector and the protocol when fees are withdrawn
     if fee > 0 {
-        let protocol_fee = calculate_fee(fee, ctx.accounts.protocol_fee_config.fee_basis_pts);
-        let amount_after_protocol_fee = fee.saturating_sub(protocol_fee);
-        transfer_checked(
+        transfer_checked_with_hook(
             CpiContext::new(
                 ctx.accounts.token_program_base_coin.to_account_info(),
                 TransferChecked {
@@ -136,36 +141,18 @@ pub fn issue_mint_handler<'info>(
                     mint: ctx.accounts.base_coin.to_account_info(),
                     to: ctx
                         .accounts
-                        .fee_collector_base_coin_token_account
+                        .fee_vault_base_coin_token_account
                         .to_account_info(),
                     authority: ctx.accounts.payer.to_account_info(),
                 },
-            ),
-            amount_after_protocol_fee,
+            )
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            fee,
             ctx.accounts.base_coin.decimals,
         )?;
-
-        transfer_checked(
-            CpiContext::new(
-                ctx.accounts.token_program_base_coin.to_account_info(),
-                TransferChecked {
-                    from: ctx.accounts.payer_base_coin_token_account.to_account_info(),
-                    mint: ctx.accounts.base_coin.to_account_info(),
-                    to: ctx
-                        .accounts
-                        .protocol_base_coin_token_account
-                        .to_account_info(),
-                    authority: ctx.accounts.payer.to_account_info(),
-                },
-            ),
-            protocol_fee,
-            ctx.accounts.base_coin.decimals,
-        )?;
-
-        ctx.accounts.authority.load_mut()?.fees_collected += amount_after_protocol_fee;
     }
 
-    transfer_checked(
+    transfer_checked_with_hook(
         CpiContext::new(
             ctx.accounts.token_program_base_coin.to_account_info(),
             TransferChecked {
@@ -177,10 +164,18 @@ pub fn issue_mint_handler<'info>(
                     .to_account_info(),
                 authority: ctx.accounts.payer.to_account_info(),
             },
-        ),
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
         amount_after_fee,
         ctx.accounts.base_coin.decimals,
     )?;
 
-    Ok(())
+    let mint_to_base_ratio = ctx.accounts.authority.load()?.mint_to_base_ratio;
+    let accounts = &mut ctx.accounts;
+    require_solvent(
+        mint_to_base_ratio,
+        before,
+        &mut accounts.authority_base_coin_token_account,
+        &mut accounts.mint,
+    )
 }
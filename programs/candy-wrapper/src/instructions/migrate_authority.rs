@@ -0,0 +1,93 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
+    Discriminator,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    error::CustomError,
+    state::{Authority, LegacyAuthority, AUTHORITY_SPACE, LEGACY_AUTHORITY_SPACE},
+};
+
+#[derive(Accounts)]
+pub struct MigrateAuthorityCtx<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"authority", mint.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    /// CHECK: `Authority` account in the legacy layout, checked by the handler
+    pub authority: UncheckedAccount<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub base_coin: Box<InterfaceAccount<'info, Mint>>,
+    // mints created with the legacy layout paid fees out directly, so have no fee vault
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"fee_vault", mint.key().as_ref()],
+        bump,
+        token::mint = base_coin,
+        token::authority = authority,
+        token::token_program = token_program_base_coin,
+    )]
+    pub fee_vault_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program_base_coin: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// grows an `Authority` account created before the fields after `admin` were added into the
+// current layout and records its new fee vault. The other new fields start zeroed, which keeps
+// the mint behaving as it did: fees paid out in the base coin, no gift card, blocklist or
+// non-transferable token and no protocol fees owed
+pub fn migrate_authority_handler(ctx: Context<MigrateAuthorityCtx>) -> Result<()> {
+    let info = ctx.accounts.authority.to_account_info();
+    require_eq!(
+        info.data_len(),
+        LEGACY_AUTHORITY_SPACE,
+        CustomError::AuthorityIsNotLegacy
+    );
+    let legacy: LegacyAuthority = {
+        let data = info.try_borrow_data()?;
+        require!(
+            data[..8] == Authority::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        bytemuck::pod_read_unaligned(&data[8..])
+    };
+    require_keys_eq!(
+        ctx.accounts.payer.key(),
+        legacy.admin,
+        CustomError::IncorrectUpdateAuthority
+    );
+    require_keys_eq!(
+        ctx.accounts.base_coin.key(),
+        legacy.base_coin,
+        CustomError::UnauthorizedBaseCoin
+    );
+
+    let rent = Rent::get()?
+        .minimum_balance(AUTHORITY_SPACE)
+        .saturating_sub(info.lamports());
+    if rent > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            rent,
+        )?;
+    }
+    info.realloc(AUTHORITY_SPACE, true)?;
+
+    let mut data = info.try_borrow_mut_data()?;
+    let authority: &mut Authority = bytemuck::from_bytes_mut(&mut data[8..]);
+    authority.fee_vault_bump = ctx.bumps.fee_vault_base_coin_token_account;
+    Ok(())
+}
@@ -1,27 +1,58 @@
+pub mod add_merchant;
+pub mod attest_reserves;
+pub mod block_wallet;
 pub mod change_admin;
+pub mod change_compliance_officer;
 pub mod change_fee_collector;
+pub mod change_fee_payout;
 pub mod change_issuance_fee;
 pub mod change_redemption_fee;
 pub mod change_transfer_fee;
+pub mod change_transfer_hook;
+pub mod clear_fee_split;
 pub mod close_account;
 pub mod create_mint;
 pub mod create_mint_metadata;
+pub mod distribute;
+pub mod execute;
+pub mod freeze_account;
+pub mod harvest_and_withdraw_fees;
 pub mod issue_mint;
+pub mod migrate_authority;
 pub mod protocol;
 pub mod redeem_basecoin;
+pub mod remove_merchant;
+pub mod set_fee_split;
 pub mod set_to_immutable;
+pub mod thaw_account;
+pub mod unblock_wallet;
 pub mod withdraw_fees;
 
+pub use add_merchant::*;
+pub use attest_reserves::*;
+pub use block_wallet::*;
 pub use change_admin::*;
+pub use change_compliance_officer::*;
 pub use change_fee_collector::*;
+pub use change_fee_payout::*;
 pub use change_issuance_fee::*;
 pub use change_redemption_fee::*;
 pub use change_transfer_fee::*;
+pub use change_transfer_hook::*;
+pub use clear_fee_split::*;
 pub use close_account::*;
 pub use create_mint::*;
 pub use create_mint_metadata::*;
+pub use distribute::*;
+pub use execute::*;
+pub use freeze_account::*;
 pub use issue_mint::*;
+pub use migrate_authority::*;
 pub use protocol::*;
 pub use redeem_basecoin::*;
+pub use remove_merchant::*;
+pub use set_fee_split::*;
 pub use set_to_immutable::*;
+pub use thaw_account::*;
+pub use unblock_wallet::*;
 pub use withdraw_fees::*;
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount, TransferChecked},
+};
+
+use crate::{
+    error::CustomError,
+    state::{Authority, PROTOCOL_WALLET},
+    utils::transfer_checked_with_hook,
+};
+
+// number of remaining accounts passed per mint: authority, fee vault base coin token account,
+// base coin, protocol base coin token account and base coin token program. The accounts needed
+// by base coin transfer hooks follow those of the `mints` claimed
+pub const CLAIM_ACCOUNTS_PER_MINT: usize = 5;
+
+#[derive(Accounts)]
+pub struct ClaimProtocolFeesCtx<'info> {
+    pub payer: Signer<'info>,
+}
+
+pub fn claim_protocol_fees_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimProtocolFeesCtx<'info>>,
+    mints: u8,
+) -> Result<()> {
+    let claim_accounts_len = usize::from(mints) * CLAIM_ACCOUNTS_PER_MINT;
+    require!(
+        ctx.remaining_accounts.len() >= claim_accounts_len,
+        CustomError::IncorrectProtocolFeeClaimAccounts
+    );
+    let mints = ctx.remaining_accounts[..claim_accounts_len].chunks_exact(CLAIM_ACCOUNTS_PER_MINT);
+
+    for accounts in mints {
+        let authority_info = &accounts[0];
+        let fee_vault_base_coin_token_account_info = &accounts[1];
+        let base_coin_info = &accounts[2];
+        let protocol_base_coin_token_account_info = &accounts[3];
+        let token_program_base_coin = &accounts[4];
+
+        let authority_loader = AccountLoader::<Authority>::try_from(authority_info)?;
+        let (mint, bump, fee_vault_bump, base_coin, protocol_fees_accrued) = {
+            let authority = authority_loader.load()?;
+            (
+                authority.mint,
+                authority.bump,
+                authority.fee_vault_bump,
+                authority.base_coin,
+                authority.protocol_fees_accrued,
+            )
+        };
+        if protocol_fees_accrued == 0 {
+            continue;
+        }
+
+        let base_coin_mint = InterfaceAccount::<Mint>::try_from(base_coin_info)?;
+        let fee_vault_base_coin_token_account =
+            InterfaceAccount::<TokenAccount>::try_from(fee_vault_base_coin_token_account_info)?;
+        let protocol_base_coin_token_account =
+            InterfaceAccount::<TokenAccount>::try_from(protocol_base_coin_token_account_info)?;
+        let fee_vault = Pubkey::create_program_address(
+            &[b"fee_vault", mint.as_ref(), &[fee_vault_bump]],
+            &crate::ID,
+        )
+        .map_err(|_| CustomError::IncorrectProtocolFeeClaimAccounts)?;
+        require!(
+            fee_vault_base_coin_token_account_info.key() == fee_vault
+                && base_coin_mint.key() == base_coin
+                && fee_vault_base_coin_token_account.mint == base_coin
+                && fee_vault_base_coin_token_account.owner == authority_info.key()
+                && protocol_base_coin_token_account.mint == base_coin
+                && protocol_base_coin_token_account.owner == PROTOCOL_WALLET
+                && (token_program_base_coin.key() == Token::id()
+                    || token_program_base_coin.key() == Token2022::id())
+                && *base_coin_info.owner == token_program_base_coin.key(),
+            CustomError::IncorrectProtocolFeeClaimAccounts
+        );
+
+        let seeds: &[&[u8]] = &[b"authority", mint.as_ref(), &[bump]];
+        let signer = &[seeds];
+
+        transfer_checked_with_hook(
+            CpiContext::new(
+                token_program_base_coin.to_account_info(),
+                TransferChecked {
+                    from: fee_vault_base_coin_token_account_info.to_account_info(),
+                    mint: base_coin_info.to_account_info(),
+                    to: protocol_base_coin_token_account_info.to_account_info(),
+                    authority: authority_info.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec())
+            .with_signer(signer),
+            protocol_fees_accrued,
+            base_coin_mint.decimals,
+        )?;
+
+        authority_loader.load_mut()?.protocol_fees_accrued = 0;
+    }
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
+pub mod claim_protocol_fees;
 pub mod set_protocol_fee;
 
+pub use claim_protocol_fees::*;
 pub use set_protocol_fee::*;
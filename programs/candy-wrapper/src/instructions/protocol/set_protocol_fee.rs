@@ -1,11 +1,14 @@
 use anchor_lang::prelude::*;
 
-use crate::state::{ProtocolFeeConfig, PROTOCOL_FEE_CONFIG_SPACE, PROTOCOL_WALLET};
+use crate::{
+    error::CustomError,
+    state::{ProtocolFeeConfig, PROTOCOL_FEE_CONFIG_SPACE, PROTOCOL_WALLET},
+};
 #[derive(Accounts)]
 pub struct SetProtocolFeeCtx<'info> {
     #[account(
         mut,
-        constraint = payer.key() == PROTOCOL_WALLET,
+        constraint = payer.key() == PROTOCOL_WALLET @CustomError::IncorrectUpdateAuthority,
     )]
     pub payer: Signer<'info>,
     #[account(
@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::CustomError, instructions::MerchantCtx};
+
+pub fn remove_merchant_handler(ctx: Context<MerchantCtx>, wallet: Pubkey) -> Result<()> {
+    let merchants = &mut ctx.accounts.merchant_registry.merchants;
+    let index = merchants
+        .iter()
+        .position(|merchant| merchant == &wallet)
+        .ok_or(CustomError::MerchantNotRegistered)?;
+    merchants.swap_remove(index);
+    Ok(())
+}
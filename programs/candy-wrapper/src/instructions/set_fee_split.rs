@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::CustomError,
+    state::{
+        Authority, FeePayout, FeeSplit, FeeSplitRecipient, FEE_SPLIT_SPACE,
+        MAX_FEE_SPLIT_RECIPIENTS,
+    },
+};
+
+#[derive(Accounts)]
+pub struct SetFeeSplitCtx<'info> {
+    #[account(
+        mut,
+        constraint = payer.key() == authority.load()?.admin @CustomError::IncorrectUpdateAuthority,
+    )]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"authority", mint.key().as_ref()],
+        bump = authority.load()?.bump,
+    )]
+    pub authority: AccountLoader<'info, Authority>,
+    #[account(
+        constraint = mint.key() == authority.load()?.mint @CustomError::IncorrectMint,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        constraint = base_coin.key() == authority.load()?.base_coin @CustomError::UnauthorizedBaseCoin,
+    )]
+    pub base_coin: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = FEE_SPLIT_SPACE,
+        seeds = [b"fee_split", mint.key().as_ref()],
+        bump,
+    )]
+    pub fee_split: Box<Account<'info, FeeSplit>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = base_coin,
+        associated_token::authority = fee_split,
+        associated_token::token_program = token_program_base_coin,
+    )]
+    pub fee_split_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program_base_coin: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_fee_split_handler(
+    ctx: Context<SetFeeSplitCtx>,
+    recipients: Vec<FeeSplitRecipient>,
+) -> Result<()> {
+    let authority = &mut ctx.accounts.authority.load_mut()?;
+    require!(authority.mutable == 1, CustomError::MintIsImmutable);
+    // `distribute` only pays out the base coin
+    require!(
+        authority.fee_payout == FeePayout::BaseCoin as u8,
+        CustomError::FeeSplitCannotPayFeesInMint
+    );
+    require!(
+        !recipients.is_empty() && recipients.len() <= MAX_FEE_SPLIT_RECIPIENTS,
+        CustomError::FeeSplitRecipientsCannotExceedMax
+    );
+    let total_basis_pts: u32 = recipients.iter().map(|r| r.basis_pts as u32).sum();
+    require!(
+        total_basis_pts == 10_000,
+        CustomError::FeeSplitBasisPtsMustTotal10000
+    );
+
+    let fee_split = &mut ctx.accounts.fee_split;
+    fee_split.bump = ctx.bumps.fee_split;
+    fee_split.mint = ctx.accounts.mint.key();
+    fee_split.base_coin = ctx.accounts.base_coin.key();
+    fee_split.recipients = recipients;
+
+    // fees charged on issuance, redemption and withdrawal now accrue into the split
+    authority.fee_collector = fee_split.key();
+    Ok(())
+}
@@ -6,7 +6,7 @@ use crate::{error::CustomError, state::Authority};
 pub struct ImmutableCtx<'info> {
     #[account(
         mut,
-        constraint = payer.key() == authority.load()?.admin,
+        constraint = payer.key() == authority.load()?.admin @CustomError::IncorrectUpdateAuthority,
     )]
     pub payer: Signer<'info>,
     #[account(mut)]
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{thaw_account, ThawAccount};
+
+use crate::{events::AccountThawed, instructions::FreezeCtx};
+
+pub fn thaw_account_handler(ctx: Context<FreezeCtx>) -> Result<()> {
+    let mint_key = ctx.accounts.mint.key();
+    let seeds: &[&[u8]] = &[
+        b"authority",
+        mint_key.as_ref(),
+        &[ctx.accounts.authority.load()?.bump],
+    ];
+    let signer = &[seeds];
+
+    thaw_account(
+        CpiContext::new(
+            ctx.accounts.token_program_mint.to_account_info(),
+            ThawAccount {
+                account: ctx.accounts.token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        )
+        .with_signer(signer),
+    )?;
+
+    emit!(AccountThawed {
+        mint: mint_key,
+        token_account: ctx.accounts.token_account.key(),
+        officer: ctx.accounts.officer.key(),
+    });
+    Ok(())
+}
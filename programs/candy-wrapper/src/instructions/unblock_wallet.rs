@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    error::CustomError,
+    events::WalletUnblocked,
+    state::{BlocklistEntry, Compliance},
+};
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct UnblockWalletCtx<'info> {
+    #[account(
+        mut,
+        constraint = officer.key() == compliance.officer @CustomError::IncorrectComplianceOfficer,
+    )]
+    pub officer: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"compliance", mint.key().as_ref()],
+        bump = compliance.bump,
+    )]
+    pub compliance: Account<'info, Compliance>,
+    #[account(
+        mut,
+        close = officer,
+        seeds = [b"blocklist", mint.key().as_ref(), wallet.as_ref()],
+        bump = blocklist_entry.bump,
+    )]
+    pub blocklist_entry: Account<'info, BlocklistEntry>,
+}
+
+// closes the wallet's blocklist entry, returning its rent to the officer
+pub fn unblock_wallet_handler(ctx: Context<UnblockWalletCtx>, wallet: Pubkey) -> Result<()> {
+    let compliance = &mut ctx.accounts.compliance;
+    compliance.blocked = compliance
+        .blocked
+        .checked_sub(1)
+        .ok_or(CustomError::MathOverflow)?;
+    emit!(WalletUnblocked {
+        mint: ctx.accounts.mint.key(),
+        wallet,
+        officer: ctx.accounts.officer.key(),
+    });
+    Ok(())
+}
@@ -1,17 +1,22 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken,
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
     token_2022::Token2022,
     token_interface::{
-        burn, transfer_checked, withdraw_withheld_tokens_from_mint, Burn, Mint, TokenAccount,
-        TokenInterface, TransferChecked, WithdrawWithheldTokensFromMint,
+        burn, thaw_account, withdraw_withheld_tokens_from_mint, Burn, Mint, ThawAccount,
+        TokenAccount, TokenInterface, TransferChecked, WithdrawWithheldTokensFromMint,
     },
 };
 
+use candy_wrapper_math::ProtocolFeeSplit;
+
 use crate::{
     error::CustomError,
-    state::{Authority, ProtocolFeeConfig, PROTOCOL_WALLET},
-    utils::{calculate_base_coin_amount, calculate_fee, get_withheld_fee},
+    state::{Authority, FeePayout, ProtocolFeeConfig, PROTOCOL_WALLET},
+    utils::{
+        backing, calculate_base_coin_amount, get_post_fee_amount, get_withheld_fee,
+        require_solvent, split_protocol_fee, transfer_checked_with_hook,
+    },
 };
 #[derive(Accounts)]
 pub struct WithdrawFeesCtx<'info> {
@@ -38,11 +43,6 @@ pub struct WithdrawFeesCtx<'info> {
         bump = protocol_fee_config.bump,
     )]
     pub protocol_fee_config: Box<Account<'info, ProtocolFeeConfig>>,
-    #[account(
-        address = PROTOCOL_WALLET
-    )]
-    /// CHECK: Checked by address
-    pub protocol_wallet: AccountInfo<'info>,
 
     #[account(
         init_if_needed,
@@ -61,20 +61,37 @@ pub struct WithdrawFeesCtx<'info> {
     )]
     pub authority_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
-        init_if_needed,
-        payer = payer,
-        associated_token::mint = base_coin,
-        associated_token::authority = protocol_wallet,
-        associated_token::token_program = token_program_base_coin,
+        mut,
+        seeds = [b"fee_vault", mint.key().as_ref()],
+        bump = authority.load()?.fee_vault_bump,
+        token::mint = base_coin,
+        token::authority = authority,
+        token::token_program = token_program_base_coin,
     )]
-    pub protocol_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub fee_vault_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // the associated token account, so fees paid to a fee split always reach `distribute`
     #[account(
         mut,
+        address = get_associated_token_address_with_program_id(
+            &authority.load()?.fee_collector,
+            &base_coin.key(),
+            &token_program_base_coin.key(),
+        ) @CustomError::IncorrectFeeCollector,
         token::mint = base_coin,
         token::token_program = token_program_base_coin,
-        constraint = fee_collector_base_coin_token_account.owner == authority.load()?.fee_collector @CustomError::IncorrectFeeCollector,
     )]
     pub fee_collector_base_coin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &authority.load()?.fee_collector,
+            &mint.key(),
+            &token_program_mint.key(),
+        ) @CustomError::IncorrectFeeCollector,
+        token::mint = mint,
+        token::token_program = token_program_mint,
+    )]
+    pub fee_collector_mint_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
         address = Token2022::id()
@@ -88,7 +105,16 @@ pub struct WithdrawFeesCtx<'info> {
 pub fn withdraw_fees_handler<'info>(
     ctx: Context<'_, '_, '_, 'info, WithdrawFeesCtx<'info>>,
 ) -> Result<()> {
-    let withheld_amount = get_withheld_fee(&ctx.accounts.mint.to_account_info())?;
+    thaw_authority_mint_token_account(&ctx)?;
+    withdraw_and_convert_fees(ctx, 0)
+}
+
+// the authority's mint token account is created frozen on default frozen mints, and the
+// authority, as their freeze authority, thaws it before withheld fees are withdrawn into it
+pub fn thaw_authority_mint_token_account(ctx: &Context<WithdrawFeesCtx>) -> Result<()> {
+    if !ctx.accounts.authority_mint_token_account.is_frozen() {
+        return Ok(());
+    }
 
     let mint_key = ctx.accounts.mint.key();
     let seeds: &[&[u8]] = &[
@@ -98,18 +124,84 @@ pub fn withdraw_fees_handler<'info>(
     ];
     let signer = &[seeds];
 
-    withdraw_withheld_tokens_from_mint(
+    thaw_account(
         CpiContext::new(
             ctx.accounts.token_program_mint.to_account_info(),
-            WithdrawWithheldTokensFromMint {
-                token_program_id: ctx.accounts.token_program_mint.to_account_info(),
+            ThawAccount {
+                account: ctx.accounts.authority_mint_token_account.to_account_info(),
                 mint: ctx.accounts.mint.to_account_info(),
-                destination: ctx.accounts.authority_mint_token_account.to_account_info(),
                 authority: ctx.accounts.authority.to_account_info(),
             },
         )
         .with_signer(signer),
-    )?;
+    )
+}
+
+// pays out the issuance and redemption fees waiting in the fee vault, then withdraws the fees
+// withheld in the mint, burns them together with any fees already harvested into the
+// authority's mint token account and pays out their base coin value
+pub fn withdraw_and_convert_fees<'info>(
+    mut ctx: Context<'_, '_, '_, 'info, WithdrawFeesCtx<'info>>,
+    harvested_amount: u64,
+) -> Result<()> {
+    let before = backing(
+        &ctx.accounts.authority_base_coin_token_account,
+        &ctx.accounts.mint,
+    );
+    convert_fees(&ctx, harvested_amount)?;
+
+    let mint_to_base_ratio = ctx.accounts.authority.load()?.mint_to_base_ratio;
+    let accounts = &mut ctx.accounts;
+    require_solvent(
+        mint_to_base_ratio,
+        before,
+        &mut accounts.authority_base_coin_token_account,
+        &mut accounts.mint,
+    )
+}
+
+fn convert_fees<'info>(
+    ctx: &Context<'_, '_, '_, 'info, WithdrawFeesCtx<'info>>,
+    harvested_amount: u64,
+) -> Result<()> {
+    sweep_fee_vault(ctx)?;
+
+    let mint_withheld_amount = get_withheld_fee(&ctx.accounts.mint.to_account_info())?;
+    let withheld_amount = mint_withheld_amount
+        .checked_add(harvested_amount)
+        .ok_or(CustomError::MathOverflow)?;
+
+    let mint_key = ctx.accounts.mint.key();
+    let seeds: &[&[u8]] = &[
+        b"authority",
+        mint_key.as_ref(),
+        &[ctx.accounts.authority.load()?.bump],
+    ];
+    let signer = &[seeds];
+
+    // non-transferable mints have no transfer fee config to withdraw from
+    if mint_withheld_amount > 0 {
+        withdraw_withheld_tokens_from_mint(
+            CpiContext::new(
+                ctx.accounts.token_program_mint.to_account_info(),
+                WithdrawWithheldTokensFromMint {
+                    token_program_id: ctx.accounts.token_program_mint.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    destination: ctx.accounts.authority_mint_token_account.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            )
+            .with_signer(signer),
+        )?;
+    }
+
+    if withheld_amount == 0 {
+        return Ok(());
+    }
+
+    if ctx.accounts.authority.load()?.fee_payout == FeePayout::Mint as u8 {
+        return pay_fees_in_mint(ctx, withheld_amount);
+    }
 
     burn(
         CpiContext::new(
@@ -128,15 +220,17 @@ pub fn withdraw_fees_handler<'info>(
         withheld_amount,
         ctx.accounts.authority_base_coin_token_account.amount,
         ctx.accounts.mint.supply,
-    );
+    )?;
 
-    let fee = calculate_fee(
+    let ProtocolFeeSplit {
+        protocol_fee: fee,
+        amount_after_protocol_fee: amount_after_fee,
+    } = split_protocol_fee(
         base_coin_amount,
         ctx.accounts.protocol_fee_config.fee_basis_pts,
-    );
-    let amount_after_fee = base_coin_amount.saturating_sub(fee);
+    )?;
 
-    transfer_checked(
+    transfer_checked_with_hook(
         CpiContext::new(
             ctx.accounts.token_program_base_coin.to_account_info(),
             TransferChecked {
@@ -147,17 +241,121 @@ pub fn withdraw_fees_handler<'info>(
                 mint: ctx.accounts.base_coin.to_account_info(),
                 to: ctx
                     .accounts
-                    .protocol_base_coin_token_account
+                    .fee_collector_base_coin_token_account
                     .to_account_info(),
                 authority: ctx.accounts.authority.to_account_info(),
             },
         )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec())
+        .with_signer(signer),
+        amount_after_fee,
+        ctx.accounts.base_coin.decimals,
+    )?;
+
+    // the protocol fee is held in the fee vault until claimed
+    transfer_checked_with_hook(
+        CpiContext::new(
+            ctx.accounts.token_program_base_coin.to_account_info(),
+            TransferChecked {
+                from: ctx
+                    .accounts
+                    .authority_base_coin_token_account
+                    .to_account_info(),
+                mint: ctx.accounts.base_coin.to_account_info(),
+                to: ctx
+                    .accounts
+                    .fee_vault_base_coin_token_account
+                    .to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec())
         .with_signer(signer),
         fee,
         ctx.accounts.base_coin.decimals,
     )?;
 
-    transfer_checked(
+    // a base coin charging transfer fees delivers less than is sent, so record what arrived
+    let base_coin_info = ctx.accounts.base_coin.to_account_info();
+    let authority = &mut ctx.accounts.authority.load_mut()?;
+    authority.fees_collected = authority
+        .fees_collected
+        .checked_add(get_post_fee_amount(&base_coin_info, amount_after_fee)?)
+        .ok_or(CustomError::MathOverflow)?;
+    authority.protocol_fees_accrued = authority
+        .protocol_fees_accrued
+        .checked_add(get_post_fee_amount(&base_coin_info, fee)?)
+        .ok_or(CustomError::MathOverflow)?;
+
+    Ok(())
+}
+
+// settles the protocol share of the withheld tokens in base coin and transfers the rest to
+// the fee collector without burning them
+fn pay_fees_in_mint<'info>(
+    ctx: &Context<'_, '_, '_, 'info, WithdrawFeesCtx<'info>>,
+    withheld_amount: u64,
+) -> Result<()> {
+    let fee_collector_mint_token_account =
+        ctx.accounts
+            .fee_collector_mint_token_account
+            .as_ref()
+            .ok_or(CustomError::MissingFeeCollectorMintTokenAccount)?;
+
+    let mint_key = ctx.accounts.mint.key();
+    let seeds: &[&[u8]] = &[
+        b"authority",
+        mint_key.as_ref(),
+        &[ctx.accounts.authority.load()?.bump],
+    ];
+    let signer = &[seeds];
+
+    let ProtocolFeeSplit {
+        protocol_fee: fee,
+        amount_after_protocol_fee: amount_after_fee,
+    } = split_protocol_fee(
+        withheld_amount,
+        ctx.accounts.protocol_fee_config.fee_basis_pts,
+    )?;
+
+    burn(
+        CpiContext::new(
+            ctx.accounts.token_program_mint.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint.to_account_info(),
+                from: ctx.accounts.authority_mint_token_account.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        )
+        .with_signer(signer),
+        fee,
+    )?;
+
+    let protocol_base_coin_amount = calculate_base_coin_amount(
+        fee,
+        ctx.accounts.authority_base_coin_token_account.amount,
+        ctx.accounts.mint.supply,
+    )?;
+
+    // this is an ordinary token transfer, so the mint's own transfer fee and hook apply to it
+    transfer_checked_with_hook(
+        CpiContext::new(
+            ctx.accounts.token_program_mint.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.authority_mint_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: fee_collector_mint_token_account.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec())
+        .with_signer(signer),
+        amount_after_fee,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    // the protocol fee is held in the fee vault until claimed
+    transfer_checked_with_hook(
         CpiContext::new(
             ctx.accounts.token_program_base_coin.to_account_info(),
             TransferChecked {
@@ -168,16 +366,102 @@ pub fn withdraw_fees_handler<'info>(
                 mint: ctx.accounts.base_coin.to_account_info(),
                 to: ctx
                     .accounts
-                    .fee_collector_base_coin_token_account
+                    .fee_vault_base_coin_token_account
                     .to_account_info(),
                 authority: ctx.accounts.authority.to_account_info(),
             },
         )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec())
         .with_signer(signer),
+        protocol_base_coin_amount,
+        ctx.accounts.base_coin.decimals,
+    )?;
+
+    // fees collected are tracked in base coin, so record the value of the tokens paid out
+    let fee_collector_base_coin_amount = calculate_base_coin_amount(
         amount_after_fee,
+        ctx.accounts.authority_base_coin_token_account.amount,
+        ctx.accounts.mint.supply,
+    )?;
+    let authority = &mut ctx.accounts.authority.load_mut()?;
+    authority.fees_collected = authority
+        .fees_collected
+        .checked_add(fee_collector_base_coin_amount)
+        .ok_or(CustomError::MathOverflow)?;
+    authority.protocol_fees_accrued = authority
+        .protocol_fees_accrued
+        .checked_add(get_post_fee_amount(
+            &ctx.accounts.base_coin.to_account_info(),
+            protocol_base_coin_amount,
+        )?)
+        .ok_or(CustomError::MathOverflow)?;
+
+    Ok(())
+}
+
+// splits the fees that accrued in the fee vault since the last withdrawal, paying the fee
+// collector and leaving the protocol share in the vault until claimed
+fn sweep_fee_vault<'info>(ctx: &Context<'_, '_, '_, 'info, WithdrawFeesCtx<'info>>) -> Result<()> {
+    let unsplit_amount = ctx
+        .accounts
+        .fee_vault_base_coin_token_account
+        .amount
+        .saturating_sub(ctx.accounts.authority.load()?.protocol_fees_accrued);
+    if unsplit_amount == 0 {
+        return Ok(());
+    }
+
+    let mint_key = ctx.accounts.mint.key();
+    let seeds: &[&[u8]] = &[
+        b"authority",
+        mint_key.as_ref(),
+        &[ctx.accounts.authority.load()?.bump],
+    ];
+    let signer = &[seeds];
+
+    let ProtocolFeeSplit {
+        protocol_fee,
+        amount_after_protocol_fee,
+    } = split_protocol_fee(
+        unsplit_amount,
+        ctx.accounts.protocol_fee_config.fee_basis_pts,
+    )?;
+
+    transfer_checked_with_hook(
+        CpiContext::new(
+            ctx.accounts.token_program_base_coin.to_account_info(),
+            TransferChecked {
+                from: ctx
+                    .accounts
+                    .fee_vault_base_coin_token_account
+                    .to_account_info(),
+                mint: ctx.accounts.base_coin.to_account_info(),
+                to: ctx
+                    .accounts
+                    .fee_collector_base_coin_token_account
+                    .to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec())
+        .with_signer(signer),
+        amount_after_protocol_fee,
         ctx.accounts.base_coin.decimals,
     )?;
-    ctx.accounts.authority.load_mut()?.fees_collected += amount_after_fee;
+
+    let fee_collector_amount = get_post_fee_amount(
+        &ctx.accounts.base_coin.to_account_info(),
+        amount_after_protocol_fee,
+    )?;
+    let authority = &mut ctx.accounts.authority.load_mut()?;
+    authority.fees_collected = authority
+        .fees_collected
+        .checked_add(fee_collector_amount)
+        .ok_or(CustomError::MathOverflow)?;
+    authority.protocol_fees_accrued = authority
+        .protocol_fees_accrued
+        .checked_add(protocol_fee)
+        .ok_or(CustomError::MathOverflow)?;
 
     Ok(())
 }
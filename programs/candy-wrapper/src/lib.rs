@@ -2,12 +2,14 @@ use anchor_lang::prelude::*;
 
 declare_id!("4iKL2rCj42ZmvtzPQBadTcNndneJR2FRtJSmc8XzXAp9");
 
-mod error;
-mod instructions;
-mod state;
+pub mod error;
+pub mod events;
+pub mod instructions;
+pub mod state;
 mod utils;
 
 use instructions::*;
+use state::{FeePayout, FeeSplitRecipient};
 #[program]
 pub mod candy_wrapper {
 
@@ -20,6 +22,13 @@ pub mod candy_wrapper {
         instructions::set_protocol_fee::set_protocol_fee_handler(ctx, fee_basis_pts)
     }
 
+    pub fn claim_protocol_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimProtocolFeesCtx<'info>>,
+        mints: u8,
+    ) -> Result<()> {
+        instructions::claim_protocol_fees::claim_protocol_fees_handler(ctx, mints)
+    }
+
     pub fn set_to_immutable(ctx: Context<ImmutableCtx>) -> Result<()> {
         instructions::set_to_immutable::set_to_immutable_handler(ctx)
     }
@@ -28,7 +37,9 @@ pub mod candy_wrapper {
         instructions::change_admin::change_admin_handler(ctx, new_admin)
     }
 
-    pub fn close_account(ctx: Context<CloseAccountCtx>) -> Result<()> {
+    pub fn close_account<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseAccountCtx<'info>>,
+    ) -> Result<()> {
         instructions::close_account::close_account_handler(ctx)
     }
 
@@ -39,6 +50,10 @@ pub mod candy_wrapper {
         instructions::change_fee_collector::change_fee_collector_handler(ctx, new_fee_collector)
     }
 
+    pub fn change_fee_payout(ctx: Context<FeePayoutCtx>, fee_payout: FeePayout) -> Result<()> {
+        instructions::change_fee_payout::change_fee_payout_handler(ctx, fee_payout)
+    }
+
     pub fn create_mint(ctx: Context<CreateMintCtx>, args: CreateMintArgs) -> Result<()> {
         instructions::create_mint::create_mint_handler(ctx, args)
     }
@@ -63,6 +78,13 @@ pub mod candy_wrapper {
         instructions::change_transfer_fee::change_transfer_fee_handler(ctx, fee_basis_pts, max_fee)
     }
 
+    pub fn change_transfer_hook(
+        ctx: Context<TransferHookCtx>,
+        program_id: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::change_transfer_hook::change_transfer_hook_handler(ctx, program_id)
+    }
+
     pub fn issue_mint<'info>(
         ctx: Context<'_, '_, '_, 'info, IssueMintCtx<'info>>,
         amount: u64,
@@ -83,6 +105,12 @@ pub mod candy_wrapper {
         instructions::withdraw_fees::withdraw_fees_handler(ctx)
     }
 
+    pub fn harvest_and_withdraw_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawFeesCtx<'info>>,
+    ) -> Result<()> {
+        instructions::harvest_and_withdraw_fees::harvest_and_withdraw_fees_handler(ctx)
+    }
+
     pub fn change_issuance_fee(ctx: Context<IssuanceFeeCtx>, fee_basis_pts: u16) -> Result<()> {
         instructions::change_issuance_fee::change_issuance_fee_handler(ctx, fee_basis_pts)
     }
@@ -90,4 +118,68 @@ pub mod candy_wrapper {
     pub fn change_redemption_fee(ctx: Context<RedemptionFeeCtx>, fee_basis_pts: u16) -> Result<()> {
         instructions::change_redemption_fee::change_redemption_fee_handler(ctx, fee_basis_pts)
     }
+
+    pub fn set_fee_split(
+        ctx: Context<SetFeeSplitCtx>,
+        recipients: Vec<FeeSplitRecipient>,
+    ) -> Result<()> {
+        instructions::set_fee_split::set_fee_split_handler(ctx, recipients)
+    }
+
+    pub fn distribute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeCtx<'info>>,
+    ) -> Result<()> {
+        instructions::distribute::distribute_handler(ctx)
+    }
+
+    pub fn clear_fee_split<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClearFeeSplitCtx<'info>>,
+        new_fee_collector: Pubkey,
+    ) -> Result<()> {
+        instructions::clear_fee_split::clear_fee_split_handler(ctx, new_fee_collector)
+    }
+
+    pub fn migrate_authority(ctx: Context<MigrateAuthorityCtx>) -> Result<()> {
+        instructions::migrate_authority::migrate_authority_handler(ctx)
+    }
+
+    pub fn attest_reserves(ctx: Context<AttestReservesCtx>) -> Result<()> {
+        instructions::attest_reserves::attest_reserves_handler(ctx)
+    }
+
+    pub fn add_merchant(ctx: Context<MerchantCtx>, wallet: Pubkey) -> Result<()> {
+        instructions::add_merchant::add_merchant_handler(ctx, wallet)
+    }
+
+    pub fn remove_merchant(ctx: Context<MerchantCtx>, wallet: Pubkey) -> Result<()> {
+        instructions::remove_merchant::remove_merchant_handler(ctx, wallet)
+    }
+
+    pub fn change_compliance_officer(
+        ctx: Context<ComplianceOfficerCtx>,
+        officer: Pubkey,
+    ) -> Result<()> {
+        instructions::change_compliance_officer::change_compliance_officer_handler(ctx, officer)
+    }
+
+    pub fn block_wallet(ctx: Context<BlockWalletCtx>, wallet: Pubkey) -> Result<()> {
+        instructions::block_wallet::block_wallet_handler(ctx, wallet)
+    }
+
+    pub fn unblock_wallet(ctx: Context<UnblockWalletCtx>, wallet: Pubkey) -> Result<()> {
+        instructions::unblock_wallet::unblock_wallet_handler(ctx, wallet)
+    }
+
+    pub fn freeze_account(ctx: Context<FreezeCtx>) -> Result<()> {
+        instructions::freeze_account::freeze_account_handler(ctx)
+    }
+
+    pub fn thaw_account(ctx: Context<FreezeCtx>) -> Result<()> {
+        instructions::thaw_account::thaw_account_handler(ctx)
+    }
+
+    #[interface(spl_transfer_hook_interface::execute)]
+    pub fn execute(ctx: Context<ExecuteCtx>, amount: u64) -> Result<()> {
+        instructions::execute::execute_handler(ctx, amount)
+    }
 }
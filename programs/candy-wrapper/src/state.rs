@@ -13,9 +13,39 @@ pub struct Authority {
     pub mint: Pubkey,
     pub fee_collector: Pubkey,
     pub admin: Pubkey,
+    pub fee_payout: u8,
+    pub fee_vault_bump: u8,
+    pub gift_card: u8,
+    pub blocklist: u8,
+    pub non_transferable: u8,
+    pub padding: [u8; 3],
+    pub protocol_fees_accrued: u64,
 }
 pub const AUTHORITY_SPACE: usize = 8 + std::mem::size_of::<Authority>();
 
+// layout of `Authority` accounts created before the fields after `admin` were added, which
+// `migrate_authority` grows into the current one
+#[zero_copy]
+pub struct LegacyAuthority {
+    pub fees_collected: u64,
+    pub mint_to_base_ratio: u16,
+    pub issuance_fee_basis_pts: u16,
+    pub redemption_fee_basis_pts: u16,
+    pub mutable: u8,
+    pub bump: u8,
+    pub base_coin: Pubkey,
+    pub mint: Pubkey,
+    pub fee_collector: Pubkey,
+    pub admin: Pubkey,
+}
+pub const LEGACY_AUTHORITY_SPACE: usize = 8 + std::mem::size_of::<LegacyAuthority>();
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FeePayout {
+    BaseCoin,
+    Mint,
+}
+
 #[account]
 pub struct ProtocolFeeConfig {
     pub bump: u8,
@@ -24,5 +54,100 @@ pub struct ProtocolFeeConfig {
 
 pub const PROTOCOL_FEE_CONFIG_SPACE: usize = 8 + std::mem::size_of::<ProtocolFeeConfig>();
 
+#[account]
+pub struct FeeSplit {
+    pub bump: u8,
+    pub mint: Pubkey,
+    pub base_coin: Pubkey,
+    pub recipients: Vec<FeeSplitRecipient>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FeeSplitRecipient {
+    pub wallet: Pubkey,
+    pub basis_pts: u16,
+}
+
+pub const MAX_FEE_SPLIT_RECIPIENTS: usize = 8;
+pub const FEE_SPLIT_SPACE: usize = 8 + 1 + 32 + 32 + 4 + MAX_FEE_SPLIT_RECIPIENTS * (32 + 2);
+
+// wallets of a gift card mint that its holders may transfer to, or receive from
+#[account]
+pub struct MerchantRegistry {
+    pub bump: u8,
+    pub mint: Pubkey,
+    pub merchants: Vec<Pubkey>,
+}
+
+pub const MAX_MERCHANTS: usize = 64;
+pub const MERCHANT_REGISTRY_SPACE: usize = 8 + 1 + 32 + 4 + MAX_MERCHANTS * 32;
+
+// wallet allowed to block and unblock holders of a mint
+#[account]
+pub struct Compliance {
+    pub bump: u8,
+    pub mint: Pubkey,
+    pub officer: Pubkey,
+    // wallets with a blocklist entry, which must all be unblocked before the mint is closed
+    pub blocked: u32,
+}
+
+pub const COMPLIANCE_SPACE: usize = 8 + 1 + 32 + 32 + 4;
+
+// exists for as long as `wallet` is blocked from issuing, redeeming and transferring the mint
+#[account]
+pub struct BlocklistEntry {
+    pub bump: u8,
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+}
+
+pub const BLOCKLIST_ENTRY_SPACE: usize = 8 + 1 + 32 + 32;
+
+#[zero_copy]
+#[derive(Default)]
+pub struct AttestationRecord {
+    pub reserves: u64,
+    pub supply: u64,
+    // transfer fees withheld in the mint, still part of the supply until withdrawn
+    pub withheld: u64,
+    // reserves relative to the peg, 10_000 when the supply is backed exactly at the peg
+    pub backing_ratio_basis_pts: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+pub const ATTESTATION_HISTORY_LEN: usize = 32;
+
+#[account(zero_copy)]
+pub struct ReserveAttestation {
+    pub mint: Pubkey,
+    pub bump: u8,
+    pub padding: [u8; 7],
+    // attestations made so far, the latest one is at `(count - 1) % ATTESTATION_HISTORY_LEN`
+    pub count: u64,
+    pub history: [AttestationRecord; ATTESTATION_HISTORY_LEN],
+}
+pub const RESERVE_ATTESTATION_SPACE: usize = 8 + std::mem::size_of::<ReserveAttestation>();
+
+impl ReserveAttestation {
+    pub fn push(&mut self, record: AttestationRecord) {
+        self.history[self.count as usize % ATTESTATION_HISTORY_LEN] = record;
+        self.count += 1;
+    }
+
+    pub fn latest(&self) -> Option<&AttestationRecord> {
+        let index = self.count.checked_sub(1)? as usize % ATTESTATION_HISTORY_LEN;
+        Some(&self.history[index])
+    }
+
+    // the attestations still held, oldest first
+    pub fn records(&self) -> impl Iterator<Item = &AttestationRecord> {
+        let held = (self.count as usize).min(ATTESTATION_HISTORY_LEN);
+        let oldest = self.count as usize - held;
+        (oldest..self.count as usize).map(|index| &self.history[index % ATTESTATION_HISTORY_LEN])
+    }
+}
+
 // multi-sig wallet
 pub const PROTOCOL_WALLET: Pubkey = pubkey!("G6kBnedts6uAivtY72ToaFHBs1UVbT9udiXmQZgMEjoF");
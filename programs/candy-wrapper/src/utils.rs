@@ -1,76 +1,172 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_2022::spl_token_2022::{
-    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+    self,
+    extension::{
+        transfer_fee::{self, TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    onchain::invoke_transfer_checked,
     state,
 };
+use anchor_spl::token_interface::{Mint, TokenAccount, TransferChecked};
 
+use candy_wrapper_math::{Backing, ProtocolFeeSplit, TransferFee, TransferFeeSchedule};
+
+use crate::error::CustomError;
+
+// zero for non-transferable mints, which charge no transfer fees
 pub fn get_withheld_fee(mint_info: &AccountInfo) -> Result<u64> {
     let mint_data = mint_info.try_borrow_data()?;
     let mint = StateWithExtensions::<state::Mint>::unpack(&mint_data)?;
-    let extension = mint.get_extension::<TransferFeeConfig>()?;
+    let Ok(extension) = mint.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
     let withheld_amount = u64::from(extension.withheld_amount);
     Ok(withheld_amount)
 }
 
-fn ceil_div(numerator: u128, denominator: u128) -> Option<u128> {
-    numerator
-        .checked_add(denominator)?
-        .checked_sub(1)?
-        .checked_div(denominator)
+// zero for token accounts of mints without transfer fees
+pub fn get_account_withheld_fee(token_account_info: &AccountInfo) -> Result<u64> {
+    let account_data = token_account_info.try_borrow_data()?;
+    let account = StateWithExtensions::<state::Account>::unpack(&account_data)?;
+    let Ok(extension) = account.get_extension::<TransferFeeAmount>() else {
+        return Ok(0);
+    };
+    let withheld_amount = u64::from(extension.withheld_amount);
+    Ok(withheld_amount)
+}
+
+// fee withheld from `amount` when it is transferred in the current epoch, zero unless the
+// mint belongs to Token-2022 and charges transfer fees
+pub fn get_transfer_fee(mint_info: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<state::Mint>::unpack(&mint_data)?;
+    let Ok(extension) = mint.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+    let fee = transfer_fee_schedule(extension)
+        .calculate_epoch_fee(Clock::get()?.epoch, amount)
+        .map_err(CustomError::from)?;
+    Ok(fee)
 }
 
-pub fn calculate_fee(amount: u64, transfer_fee_basis_pts: u16) -> u64 {
-    let transfer_fee_basis_points = transfer_fee_basis_pts as u128;
-    if transfer_fee_basis_points == 0 || amount == 0 {
-        0
-    } else {
-        let numerator = (amount as u128)
-            .checked_mul(transfer_fee_basis_points)
-            .unwrap();
-        ceil_div(numerator, 10_000)
-            .unwrap()
-            .try_into()
-            .ok()
-            .unwrap()
+fn transfer_fee_schedule(config: &TransferFeeConfig) -> TransferFeeSchedule {
+    let to_transfer_fee = |fee: &transfer_fee::TransferFee| TransferFee {
+        epoch: fee.epoch.into(),
+        maximum_fee: fee.maximum_fee.into(),
+        transfer_fee_basis_pts: fee.transfer_fee_basis_points.into(),
+    };
+    TransferFeeSchedule {
+        older_transfer_fee: to_transfer_fee(&config.older_transfer_fee),
+        newer_transfer_fee: to_transfer_fee(&config.newer_transfer_fee),
     }
 }
 
+// amount that arrives when `amount` of the mint is transferred
+pub fn get_post_fee_amount(mint_info: &AccountInfo, amount: u64) -> Result<u64> {
+    Ok(amount - get_transfer_fee(mint_info, amount)?)
+}
+
+pub fn calculate_fee(amount: u64, fee_basis_pts: u16) -> Result<u64> {
+    Ok(candy_wrapper_math::calculate_fee(amount, fee_basis_pts).map_err(CustomError::from)?)
+}
+
+pub fn calculate_share(amount: u64, share_basis_pts: u16) -> Result<u64> {
+    Ok(candy_wrapper_math::calculate_share(amount, share_basis_pts).map_err(CustomError::from)?)
+}
+
+pub fn split_protocol_fee(fee: u64, protocol_fee_basis_pts: u16) -> Result<ProtocolFeeSplit> {
+    Ok(
+        candy_wrapper_math::split_protocol_fee(fee, protocol_fee_basis_pts)
+            .map_err(CustomError::from)?,
+    )
+}
+
 pub fn calculate_mint_amount(
     base_coin_amount: u64,
     mint_to_base_ratio: u16,
     authority_base_coin_amount: u64,
     mint_supply_amount: u64,
-) -> u64 {
-    if mint_supply_amount > 0 {
-        (base_coin_amount as u128)
-            .checked_mul(mint_supply_amount as u128)
-            .unwrap()
-            .checked_div(authority_base_coin_amount as u128)
-            .unwrap()
-            .try_into()
-            .ok()
-            .unwrap()
-    } else {
-        (base_coin_amount as u128)
-            .checked_mul(mint_to_base_ratio as u128)
-            .unwrap()
-            .try_into()
-            .ok()
-            .unwrap()
-    }
+) -> Result<u64> {
+    Ok(candy_wrapper_math::calculate_mint_amount(
+        base_coin_amount,
+        mint_to_base_ratio,
+        authority_base_coin_amount,
+        mint_supply_amount,
+    )
+    .map_err(CustomError::from)?)
 }
 
 pub fn calculate_base_coin_amount(
     mint_amount: u64,
     authority_base_coin_amount: u64,
     mint_supply_amount: u64,
-) -> u64 {
-    (mint_amount as u128)
-        .checked_mul(authority_base_coin_amount as u128)
-        .unwrap()
-        .checked_div(mint_supply_amount as u128)
-        .unwrap()
-        .try_into()
-        .ok()
-        .unwrap()
+) -> Result<u64> {
+    Ok(candy_wrapper_math::calculate_base_coin_amount(
+        mint_amount,
+        authority_base_coin_amount,
+        mint_supply_amount,
+    )
+    .map_err(CustomError::from)?)
+}
+
+pub fn backing(
+    reserves: &InterfaceAccount<TokenAccount>,
+    mint: &InterfaceAccount<Mint>,
+) -> Backing {
+    Backing {
+        reserves: reserves.amount,
+        supply: mint.supply,
+    }
+}
+
+// reloads the vault and the mint after the CPIs of an operation and fails unless they still
+// cover the peg and no token lost backing, so a pricing bug cannot drain the vault
+pub fn require_solvent<'info>(
+    mint_to_base_ratio: u16,
+    before: Backing,
+    reserves: &mut InterfaceAccount<'info, TokenAccount>,
+    mint: &mut InterfaceAccount<'info, Mint>,
+) -> Result<()> {
+    reserves.reload()?;
+    mint.reload()?;
+    let after = backing(reserves, mint);
+    require!(
+        after.covers_peg(mint_to_base_ratio) && after.is_at_least(&before),
+        CustomError::Undercollateralized
+    );
+    Ok(())
+}
+
+// same as `transfer_checked`, but if the mint has a transfer hook, the accounts the hook needs
+// are looked up in the context's remaining accounts and passed on to the token program
+pub fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+// whether `account_info` is a token account of `mint`
+pub fn is_token_account_of(account_info: &AccountInfo, mint: &Pubkey) -> bool {
+    let Ok(data) = account_info.try_borrow_data() else {
+        return false;
+    };
+    StateWithExtensions::<state::Account>::unpack(&data)
+        .is_ok_and(|account| account.base.mint == *mint)
 }
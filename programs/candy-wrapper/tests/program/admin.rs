@@ -0,0 +1,610 @@
+use anchor_lang::InstructionData;
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        extension::{
+            transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::Mint,
+    },
+};
+use candy_wrapper::{
+    error::CustomError,
+    instruction,
+    state::{AUTHORITY_SPACE, LEGACY_AUTHORITY_SPACE},
+};
+use candy_wrapper_client::{
+    instructions::{self, ClosableAccounts, ProtocolFeeClaim},
+    pda,
+    state::decode_fee_split,
+    FeePayout, FeeSplitRecipient,
+};
+use solana_sdk::{account::Account, instruction::Instruction, rent::Rent};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+use crate::setup::*;
+
+#[tokio::test]
+async fn admin_updates_mint() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let new_admin = env.create_user().await;
+    let fee_collector = Pubkey::new_unique();
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+
+    let authority = env.authority(&mint).await;
+    assert_eq!(authority.mint, mint);
+    assert_eq!(authority.base_coin, base_coin.mint);
+    assert_eq!(authority.admin, admin.pubkey());
+    assert_eq!(authority.mint_to_base_ratio, MINT_TO_BASE_RATIO);
+    assert_eq!(authority.mutable, 1);
+
+    env.send(
+        &[
+            instructions::change_issuance_fee(&admin.pubkey(), &mint, 25),
+            instructions::change_redemption_fee(&admin.pubkey(), &mint, 75),
+            instructions::change_fee_collector(&admin.pubkey(), &mint, fee_collector),
+            instructions::change_fee_payout(&admin.pubkey(), &mint, FeePayout::Mint),
+            instructions::change_transfer_fee(&admin.pubkey(), &mint, 10, 1_000),
+            instructions::change_admin(&admin.pubkey(), &mint, new_admin.pubkey()),
+        ],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    let authority = env.authority(&mint).await;
+    assert_eq!(authority.issuance_fee_basis_pts, 25);
+    assert_eq!(authority.redemption_fee_basis_pts, 75);
+    assert_eq!(authority.fee_collector, fee_collector);
+    assert_eq!(authority.fee_payout, FeePayout::Mint as u8);
+    assert_eq!(authority.admin, new_admin.pubkey());
+
+    // the new transfer fee takes effect two epochs later
+    let account = env.account(&mint).await.unwrap();
+    let state = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    let config = state.get_extension::<TransferFeeConfig>().unwrap();
+    assert_eq!(
+        u16::from(config.newer_transfer_fee.transfer_fee_basis_points),
+        10
+    );
+    assert_eq!(u64::from(config.newer_transfer_fee.maximum_fee), 1_000);
+
+    // the previous admin lost its rights
+    let result = env
+        .send(
+            &[instructions::change_issuance_fee(&admin.pubkey(), &mint, 0)],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::IncorrectUpdateAuthority);
+
+    env.send(
+        &[instructions::set_to_immutable(&new_admin.pubkey(), &mint)],
+        &[&new_admin],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.authority(&mint).await.mutable, 0);
+}
+
+#[tokio::test]
+async fn admin_creates_metadata() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+
+    env.send(
+        &[instructions::create_mint_metadata(
+            &admin.pubkey(),
+            &mint,
+            10_000_000,
+            "Candy".to_string(),
+            "CNDY".to_string(),
+            "https://example.com/candy.json".to_string(),
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    let account = env.account(&mint).await.unwrap();
+    let state = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    let metadata = state.get_variable_len_extension::<TokenMetadata>().unwrap();
+    assert_eq!(metadata.mint, mint);
+    assert_eq!(metadata.name, "Candy");
+    assert_eq!(metadata.symbol, "CNDY");
+    assert_eq!(metadata.uri, "https://example.com/candy.json");
+}
+
+// every instruction restricted to the admin, signed by `signer`
+fn admin_instructions(signer: &Pubkey, mint: &Pubkey, base_coin: &BaseCoin) -> Vec<Instruction> {
+    vec![
+        instructions::set_to_immutable(signer, mint),
+        instructions::change_admin(signer, mint, *signer),
+        instructions::close_account(
+            signer,
+            mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+            signer,
+            &ClosableAccounts::default(),
+        ),
+        instructions::change_fee_collector(signer, mint, *signer),
+        instructions::change_fee_payout(signer, mint, FeePayout::Mint),
+        instructions::change_transfer_fee(signer, mint, 0, 0),
+        instructions::change_issuance_fee(signer, mint, 0),
+        instructions::change_redemption_fee(signer, mint, 0),
+        instructions::change_transfer_hook(signer, mint, None),
+        instructions::change_compliance_officer(signer, mint, *signer),
+        instructions::set_fee_split(
+            signer,
+            mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+            vec![FeeSplitRecipient {
+                wallet: *signer,
+                basis_pts: 10_000,
+            }],
+        ),
+    ]
+}
+
+#[tokio::test]
+async fn admin_instructions_reject_other_signers() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let intruder = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.create_token_account(
+        &intruder.pubkey(),
+        &base_coin.mint,
+        &base_coin.token_program,
+    )
+    .await;
+
+    for ix in admin_instructions(&intruder.pubkey(), &mint, &base_coin) {
+        let result = env.send(&[ix], &[&intruder]).await;
+        assert_error(result, CustomError::IncorrectUpdateAuthority);
+    }
+}
+
+#[tokio::test]
+async fn immutable_mint_rejects_updates() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.send(
+        &[instructions::set_to_immutable(&admin.pubkey(), &mint)],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    for ix in admin_instructions(&admin.pubkey(), &mint, &base_coin) {
+        // closing only depends on the supply
+        if ix.data == (instruction::CloseAccount {}).data() {
+            continue;
+        }
+        let result = env.send(&[ix], &[&admin]).await;
+        assert_error(result, CustomError::MintIsImmutable);
+    }
+}
+
+#[tokio::test]
+async fn fee_changes_reject_more_than_100_basis_pts() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+
+    let result = env
+        .send(
+            &[instructions::change_issuance_fee(
+                &admin.pubkey(),
+                &mint,
+                101,
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::IssuanceFeeBasisPtsCannotExceed100);
+
+    let result = env
+        .send(
+            &[instructions::change_redemption_fee(
+                &admin.pubkey(),
+                &mint,
+                101,
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::RedemptionFeeBasisPtsCannotExceed100);
+}
+
+async fn close_account(
+    env: &mut Env,
+    admin: &Pubkey,
+    mint: &Pubkey,
+    base_coin: &BaseCoin,
+) -> Instruction {
+    let fee_split = env.account(&pda::fee_split(mint).0).await;
+    let closable = ClosableAccounts {
+        fee_split_recipients: fee_split.map(|account| {
+            decode_fee_split(&account.data)
+                .unwrap()
+                .recipients
+                .iter()
+                .map(|recipient| recipient.wallet)
+                .collect()
+        }),
+        merchant_registry: env.account(&pda::merchant_registry(mint).0).await.is_some(),
+        extra_account_meta_list: env
+            .account(&pda::extra_account_metas(mint).0)
+            .await
+            .is_some(),
+    };
+    instructions::close_account(
+        admin,
+        mint,
+        &base_coin.mint,
+        &base_coin.token_program,
+        &env.authority(mint).await.fee_collector,
+        &closable,
+    )
+}
+
+// empties the fee vault, which closing requires
+async fn withdraw_and_claim_fees(env: &mut Env, mint: &Pubkey, base_coin: &BaseCoin) {
+    env.withdraw_fees(mint, base_coin, &[]).await.unwrap();
+    let payer = env.payer();
+    env.send(
+        &[instructions::claim_protocol_fees(
+            &payer.pubkey(),
+            &[ProtocolFeeClaim {
+                mint: *mint,
+                base_coin: base_coin.mint,
+                token_program_base_coin: base_coin.token_program,
+            }],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+// closes the redeemed mint, checking that every account is closed with its rent returned to the
+// admin, and returns the dust that was left in the reserves
+async fn assert_closes(
+    env: &mut Env,
+    admin: &Keypair,
+    mint: &Pubkey,
+    base_coin: &BaseCoin,
+    program_accounts: &[Pubkey],
+) -> u64 {
+    let authority = pda::authority(mint).0;
+    let mut accounts = vec![
+        authority,
+        *mint,
+        pda::reserves(mint, &base_coin.mint, &base_coin.token_program),
+        pda::fee_vault(mint).0,
+        mint_token_account(&authority, mint),
+    ];
+    accounts.extend_from_slice(program_accounts);
+    let mut rent = 0;
+    for account in &accounts {
+        rent += env.account(account).await.unwrap().lamports;
+    }
+    let dust = env.reserves(mint, base_coin).await;
+    let lamports = env.account(&admin.pubkey()).await.unwrap().lamports;
+
+    let ix = close_account(env, &admin.pubkey(), mint, base_coin).await;
+    env.send(&[ix], &[admin]).await.unwrap();
+    for account in &accounts {
+        assert!(env.account(account).await.is_none());
+    }
+    assert_eq!(
+        env.account(&admin.pubkey()).await.unwrap().lamports,
+        lamports + rent
+    );
+    dust
+}
+
+// the dust in the reserves is swept to the admin as fee collector
+async fn assert_closes_to_admin(
+    env: &mut Env,
+    admin: &Keypair,
+    mint: &Pubkey,
+    base_coin: &BaseCoin,
+) {
+    let fee_collector = base_coin.token_account(&admin.pubkey());
+    let collected = env.token_balance(&fee_collector).await;
+    let dust = assert_closes(env, admin, mint, base_coin, &[]).await;
+    assert!(env.token_balance(&fee_collector).await >= collected + dust * 99 / 100);
+}
+
+#[tokio::test]
+async fn close_account_once_supply_is_redeemed() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    let other_mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000).await;
+    env.issue(&admin, &mint, &base_coin, 1_000).await.unwrap();
+
+    let ix = close_account(&mut env, &admin.pubkey(), &mint, &base_coin).await;
+    let result = env.send(&[ix], &[&admin]).await;
+    assert_error(result, CustomError::MintIsNotZero);
+
+    let ix = close_account(&mut env, &admin.pubkey(), &mint, &base_coin).await;
+    let result = env
+        .send(&[with_account(ix, 2, other_mint)], &[&admin])
+        .await;
+    assert_error(result, CustomError::IncorrectMint);
+
+    env.redeem(&admin, &mint, &base_coin, 99_000).await.unwrap();
+    let ix = close_account(&mut env, &admin.pubkey(), &mint, &base_coin).await;
+    let result = env.send(&[ix], &[&admin]).await;
+    assert_error(result, CustomError::FeeVaultIsNotEmpty);
+
+    withdraw_and_claim_fees(&mut env, &mint, &base_coin).await;
+    assert_closes_to_admin(&mut env, &admin, &mint, &base_coin).await;
+}
+
+#[tokio::test]
+async fn close_account_harvests_base_coin_transfer_fees() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin_with_transfer_fee(100, u64::MAX).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000_000)
+        .await;
+    env.issue(&admin, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    let supply = env.supply(&mint).await;
+    env.redeem(&admin, &mint, &base_coin, supply).await.unwrap();
+    withdraw_and_claim_fees(&mut env, &mint, &base_coin).await;
+    let reserves = pda::reserves(&mint, &base_coin.mint, &base_coin.token_program);
+    assert!(env.withheld_in_account(&reserves).await > 0);
+
+    assert_closes_to_admin(&mut env, &admin, &mint, &base_coin).await;
+}
+
+#[tokio::test]
+async fn close_account_closes_program_accounts_and_pays_out_fee_split() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.gift_card = true;
+    let mint = env.try_create_mint(&admin, &base_coin, args).await.unwrap();
+    env.send(
+        &[
+            instructions::set_fee_split(
+                &admin.pubkey(),
+                &mint,
+                &base_coin.mint,
+                &base_coin.token_program,
+                vec![
+                    FeeSplitRecipient {
+                        wallet: alice,
+                        basis_pts: 3_333,
+                    },
+                    FeeSplitRecipient {
+                        wallet: bob,
+                        basis_pts: 6_667,
+                    },
+                ],
+            ),
+            instructions::change_compliance_officer(&admin.pubkey(), &mint, admin.pubkey()),
+        ],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000_000)
+        .await;
+    env.issue(&admin, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    let payer = env.payer();
+    env.send(
+        &[instructions::attest_reserves(
+            &payer.pubkey(),
+            &mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    let supply = env.supply(&mint).await;
+    env.redeem(&admin, &mint, &base_coin, supply).await.unwrap();
+    withdraw_and_claim_fees(&mut env, &mint, &base_coin).await;
+    for wallet in [alice, bob] {
+        env.create_token_account(&wallet, &base_coin.mint, &base_coin.token_program)
+            .await;
+    }
+
+    // the fees left for the split are paid out in full, the last recipient taking the rounding
+    let fee_split = pda::fee_split(&mint).0;
+    let fee_split_token_account = base_coin.token_account(&fee_split);
+    let fees = env.token_balance(&fee_split_token_account).await;
+    assert!(fees > 0);
+    let dust = assert_closes(
+        &mut env,
+        &admin,
+        &mint,
+        &base_coin,
+        &[
+            fee_split,
+            fee_split_token_account,
+            pda::reserve_attestation(&mint).0,
+            pda::compliance(&mint).0,
+            pda::merchant_registry(&mint).0,
+            pda::extra_account_metas(&mint).0,
+        ],
+    )
+    .await;
+    let alice_share = (fees + dust) * 3_333 / 10_000;
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&alice)).await,
+        alice_share
+    );
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&bob)).await,
+        fees + dust - alice_share
+    );
+}
+
+#[tokio::test]
+async fn close_account_requires_the_accounts_the_mint_has() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.gift_card = true;
+    args.blocklist = true;
+    let mint = env.try_create_mint(&admin, &base_coin, args).await.unwrap();
+    env.send(
+        &[
+            instructions::set_fee_split(
+                &admin.pubkey(),
+                &mint,
+                &base_coin.mint,
+                &base_coin.token_program,
+                vec![FeeSplitRecipient {
+                    wallet: alice,
+                    basis_pts: 10_000,
+                }],
+            ),
+            instructions::change_compliance_officer(&admin.pubkey(), &mint, admin.pubkey()),
+            instructions::block_wallet(&admin.pubkey(), &mint, bob),
+        ],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    let fee_split = pda::fee_split(&mint).0;
+    let close = |closable: ClosableAccounts| {
+        instructions::close_account(
+            &admin.pubkey(),
+            &mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+            &fee_split,
+            &closable,
+        )
+    };
+    let all = || ClosableAccounts {
+        fee_split_recipients: Some(vec![alice]),
+        merchant_registry: true,
+        extra_account_meta_list: true,
+    };
+    for closable in [
+        ClosableAccounts {
+            fee_split_recipients: None,
+            ..all()
+        },
+        ClosableAccounts {
+            merchant_registry: false,
+            ..all()
+        },
+        ClosableAccounts {
+            extra_account_meta_list: false,
+            ..all()
+        },
+    ] {
+        let result = env.send(&[close(closable)], &[&admin]).await;
+        assert_error(result, CustomError::MissingClosableAccount);
+    }
+
+    // blocklist entries only close by unblocking, which must happen first
+    let result = env.send(&[close(all())], &[&admin]).await;
+    assert_error(result, CustomError::WalletsAreStillBlocked);
+    env.send(
+        &[instructions::unblock_wallet(&admin.pubkey(), &mint, bob)],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    env.send(&[close(all())], &[&admin]).await.unwrap();
+    for account in [
+        pda::authority(&mint).0,
+        fee_split,
+        pda::compliance(&mint).0,
+        pda::blocklist_entry(&mint, &bob).0,
+        pda::merchant_registry(&mint).0,
+        pda::extra_account_metas(&mint).0,
+    ] {
+        assert!(env.account(&account).await.is_none());
+    }
+}
+
+#[tokio::test]
+async fn migrate_authority_grows_legacy_authority() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let alice = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    let before = env.authority(&mint).await;
+
+    // rewind the mint to the legacy layout, which had no fee vault
+    let address = pda::authority(&mint).0;
+    let mut authority = env.account(&address).await.unwrap();
+    authority.data.truncate(LEGACY_AUTHORITY_SPACE);
+    authority.lamports = Rent::default().minimum_balance(LEGACY_AUTHORITY_SPACE);
+    env.ctx.set_account(&address, &authority.into());
+    env.ctx
+        .set_account(&pda::fee_vault(&mint).0, &Account::default().into());
+
+    let migrate = |signer: &Keypair| {
+        instructions::migrate_authority(
+            &signer.pubkey(),
+            &mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+        )
+    };
+    let result = env.send(&[migrate(&alice)], &[&alice]).await;
+    assert_error(result, CustomError::IncorrectUpdateAuthority);
+    env.send(&[migrate(&admin)], &[&admin]).await.unwrap();
+
+    let account = env.account(&address).await.unwrap();
+    assert_eq!(account.data.len(), AUTHORITY_SPACE);
+    assert_eq!(
+        account.lamports,
+        Rent::default().minimum_balance(AUTHORITY_SPACE)
+    );
+    let authority = env.authority(&mint).await;
+    assert_eq!(authority.admin, before.admin);
+    assert_eq!(authority.fee_collector, before.fee_collector);
+    assert_eq!(authority.fee_vault_bump, pda::fee_vault(&mint).1);
+    assert_eq!(authority.fee_payout, FeePayout::BaseCoin as u8);
+    assert_eq!(authority.protocol_fees_accrued, 0);
+
+    // issuance pays its fee into the recreated fee vault
+    env.fund_base_coin(&base_coin, &alice.pubkey(), 1_000_000)
+        .await;
+    env.issue(&alice, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    assert!(env.fee_vault(&mint).await > 0);
+
+    let result = env.send(&[migrate(&admin)], &[&admin]).await;
+    assert_error(result, CustomError::AuthorityIsNotLegacy);
+}
@@ -0,0 +1,133 @@
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self, extension::transfer_fee::instruction::harvest_withheld_tokens_to_mint,
+    },
+};
+use candy_wrapper::error::CustomError;
+use candy_wrapper_client::{instructions, pda, ATTESTATION_HISTORY_LEN};
+use solana_sdk::{clock::Clock, instruction::Instruction};
+
+use crate::setup::*;
+
+fn attest(payer: &Pubkey, mint: &Pubkey, base_coin: &BaseCoin) -> Instruction {
+    instructions::attest_reserves(payer, mint, &base_coin.mint, &base_coin.token_program)
+}
+
+#[tokio::test]
+async fn attest_reserves_records_backing() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let holder = Pubkey::new_unique();
+    let base_coin = env.create_base_coin(spl_token_2022::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000_000)
+        .await;
+    env.issue(&admin, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    env.transfer_mint(&admin, &holder, &mint, 10_000_000).await;
+    env.send(
+        &[harvest_withheld_tokens_to_mint(
+            &spl_token_2022::ID,
+            &mint,
+            &[&mint_token_account(&holder, &mint)],
+        )
+        .unwrap()],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // attesting is permissionless
+    let auditor = env.create_user().await;
+    env.send(&[attest(&auditor.pubkey(), &mint, &base_coin)], &[&auditor])
+        .await
+        .unwrap();
+
+    let clock: Clock = env.ctx.banks_client.get_sysvar().await.unwrap();
+    let attestation = env.reserve_attestation(&mint).await;
+    assert_eq!(attestation.mint, mint);
+    assert_eq!(attestation.count, 1);
+    let record = attestation.latest().unwrap();
+    assert_eq!(record.reserves, 990_000);
+    assert_eq!(record.supply, 99_000_000);
+    assert_eq!(record.withheld, 50_000);
+    assert_eq!(record.backing_ratio_basis_pts, 10_000);
+    assert_eq!(record.slot, clock.slot);
+    assert_eq!(record.timestamp, clock.unix_timestamp);
+
+    // burning the withheld fees for their base coin value keeps the backing
+    env.withdraw_fees(&mint, &base_coin, &[]).await.unwrap();
+    env.send(&[attest(&auditor.pubkey(), &mint, &base_coin)], &[&auditor])
+        .await
+        .unwrap();
+    let attestation = env.reserve_attestation(&mint).await;
+    let record = attestation.latest().unwrap();
+    assert_eq!(record.reserves, 989_500);
+    assert_eq!(record.supply, 98_950_000);
+    assert_eq!(record.withheld, 0);
+    assert_eq!(record.backing_ratio_basis_pts, 10_000);
+}
+
+#[tokio::test]
+async fn attestation_history_keeps_latest_records() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000_000)
+        .await;
+    let payer = env.payer().pubkey();
+
+    // one attestation per slot, each after another issuance
+    let attestations = ATTESTATION_HISTORY_LEN as u64 + 3;
+    for _ in 0..attestations {
+        env.issue(&admin, &mint, &base_coin, 1_000).await.unwrap();
+        env.send(&[attest(&payer, &mint, &base_coin)], &[])
+            .await
+            .unwrap();
+        let clock: Clock = env.ctx.banks_client.get_sysvar().await.unwrap();
+        env.ctx.warp_to_slot(clock.slot + 1).unwrap();
+    }
+
+    let attestation = env.reserve_attestation(&mint).await;
+    assert_eq!(attestation.count, attestations);
+    let records: Vec<_> = attestation.records().collect();
+    assert_eq!(records.len(), ATTESTATION_HISTORY_LEN);
+    // the three oldest were overwritten
+    assert_eq!(records[0].reserves, 4 * 990);
+    assert_eq!(records.last().unwrap().reserves, attestations * 990);
+    assert_eq!(
+        records.last().unwrap().slot,
+        attestation.latest().unwrap().slot
+    );
+    assert!(records.windows(2).all(|pair| pair[0].slot < pair[1].slot));
+}
+
+#[tokio::test]
+async fn attest_reserves_rejects_other_mint() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    let other_mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000).await;
+    env.issue(&admin, &mint, &base_coin, 1_000).await.unwrap();
+    let payer = env.payer().pubkey();
+
+    // the other mint against this mint's authority and vault
+    let ix = attest(&payer, &other_mint, &base_coin);
+    let reserves = pda::reserves(&mint, &base_coin.mint, &spl_token::ID);
+    let result = env
+        .send(
+            &[with_account(
+                with_account(ix, 3, pda::authority(&mint).0),
+                4,
+                reserves,
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, CustomError::IncorrectMint);
+}
@@ -0,0 +1,385 @@
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022::{self, error::TokenError},
+};
+use candy_wrapper::error::CustomError;
+use candy_wrapper_client::{instructions, pda, FeePayout};
+
+use crate::setup::*;
+
+#[tokio::test]
+async fn blocked_wallets_cannot_issue_redeem_or_transfer() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let officer = env.create_user().await;
+    let alice = env.create_user().await;
+    let bob = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.blocklist = true;
+    let mint = env.try_create_mint(&admin, &base_coin, args).await.unwrap();
+    env.send(
+        &[instructions::change_compliance_officer(
+            &admin.pubkey(),
+            &mint,
+            officer.pubkey(),
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    env.fund_base_coin(&base_coin, &alice.pubkey(), 1_000_000)
+        .await;
+    env.issue(&alice, &mint, &base_coin, 500_000).await.unwrap();
+
+    // blocked wallets neither receive nor send the token
+    env.send(
+        &[instructions::block_wallet(
+            &officer.pubkey(),
+            &mint,
+            bob.pubkey(),
+        )],
+        &[&officer],
+    )
+    .await
+    .unwrap();
+    let result = env
+        .try_transfer_mint(&alice, &bob.pubkey(), &mint, 1_000_000)
+        .await;
+    assert_error(result, CustomError::WalletIsBlocked);
+    env.send(
+        &[instructions::unblock_wallet(
+            &officer.pubkey(),
+            &mint,
+            bob.pubkey(),
+        )],
+        &[&officer],
+    )
+    .await
+    .unwrap();
+    env.transfer_mint(&alice, &bob.pubkey(), &mint, 1_000_000)
+        .await;
+
+    env.send(
+        &[instructions::block_wallet(
+            &officer.pubkey(),
+            &mint,
+            alice.pubkey(),
+        )],
+        &[&officer],
+    )
+    .await
+    .unwrap();
+    let result = env
+        .try_transfer_mint(&alice, &bob.pubkey(), &mint, 1_000_000)
+        .await;
+    assert_error(result, CustomError::WalletIsBlocked);
+    let result = env.issue(&alice, &mint, &base_coin, 500_000).await;
+    assert_error(result, CustomError::WalletIsBlocked);
+    let result = env.redeem(&alice, &mint, &base_coin, 1_000_000).await;
+    assert_error(result, CustomError::WalletIsBlocked);
+
+    // others are unaffected
+    env.redeem(&bob, &mint, &base_coin, 500_000).await.unwrap();
+
+    env.send(
+        &[instructions::unblock_wallet(
+            &officer.pubkey(),
+            &mint,
+            alice.pubkey(),
+        )],
+        &[&officer],
+    )
+    .await
+    .unwrap();
+    assert!(env
+        .account(&pda::blocklist_entry(&mint, &alice.pubkey()).0)
+        .await
+        .is_none());
+    env.redeem(&alice, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn blocklist_is_enforced_on_issuance_without_transfer_hook() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let alice = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.send(
+        &[
+            instructions::change_compliance_officer(&admin.pubkey(), &mint, admin.pubkey()),
+            instructions::block_wallet(&admin.pubkey(), &mint, alice.pubkey()),
+        ],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    env.fund_base_coin(&base_coin, &alice.pubkey(), 1_000_000)
+        .await;
+    let result = env.issue(&alice, &mint, &base_coin, 1_000_000).await;
+    assert_error(result, CustomError::WalletIsBlocked);
+}
+
+#[tokio::test]
+async fn blocklist_rejects_other_officers() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let officer = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.blocklist = true;
+    let mint = env.try_create_mint(&admin, &base_coin, args).await.unwrap();
+    env.send(
+        &[
+            instructions::change_compliance_officer(&admin.pubkey(), &mint, officer.pubkey()),
+            instructions::block_wallet(&officer.pubkey(), &mint, officer.pubkey()),
+        ],
+        &[&admin, &officer],
+    )
+    .await
+    .unwrap();
+
+    // the admin is not the officer
+    let result = env
+        .send(
+            &[instructions::block_wallet(
+                &admin.pubkey(),
+                &mint,
+                admin.pubkey(),
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::IncorrectComplianceOfficer);
+    let result = env
+        .send(
+            &[instructions::unblock_wallet(
+                &admin.pubkey(),
+                &mint,
+                officer.pubkey(),
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::IncorrectComplianceOfficer);
+
+    // paying fees out in the mint would invoke the program as its own transfer hook
+    let result = env
+        .send(
+            &[instructions::change_fee_payout(
+                &admin.pubkey(),
+                &mint,
+                FeePayout::Mint,
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::TransferHookCannotPayFeesInMint);
+}
+
+#[tokio::test]
+async fn only_thawed_accounts_hold_default_frozen_tokens() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let officer = env.create_user().await;
+    let alice = env.create_user().await;
+    let bob = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.freezable = true;
+    args.default_frozen = true;
+    let mint = env.try_create_mint(&admin, &base_coin, args).await.unwrap();
+    env.send(
+        &[instructions::change_compliance_officer(
+            &admin.pubkey(),
+            &mint,
+            officer.pubkey(),
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    // accounts start out frozen, so wallets must pass KYC before issuing
+    env.fund_base_coin(&base_coin, &alice.pubkey(), 1_000_000)
+        .await;
+    let result = env.issue(&alice, &mint, &base_coin, 1_000_000).await;
+    assert_error(result, TokenError::AccountFrozen as u32);
+    let alice_account = mint_token_account(&alice.pubkey(), &mint);
+    env.create_token_account(&alice.pubkey(), &mint, &spl_token_2022::ID)
+        .await;
+    env.send(
+        &[instructions::thaw_account(
+            &officer.pubkey(),
+            &mint,
+            &alice_account,
+        )],
+        &[&officer],
+    )
+    .await
+    .unwrap();
+    env.issue(&alice, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+
+    // nor can they receive the token before then
+    let result = env
+        .try_transfer_mint(&alice, &bob.pubkey(), &mint, 1_000_000)
+        .await;
+    assert_error(result, TokenError::AccountFrozen as u32);
+
+    // frozen again, alice can neither send nor redeem
+    env.send(
+        &[instructions::freeze_account(
+            &officer.pubkey(),
+            &mint,
+            &alice_account,
+        )],
+        &[&officer],
+    )
+    .await
+    .unwrap();
+    let bob_account = mint_token_account(&bob.pubkey(), &mint);
+    env.send(
+        &[instructions::thaw_account(
+            &officer.pubkey(),
+            &mint,
+            &bob_account,
+        )],
+        &[&officer],
+    )
+    .await
+    .unwrap();
+    let result = env
+        .try_transfer_mint(&alice, &bob.pubkey(), &mint, 1_000_000)
+        .await;
+    assert_error(result, TokenError::AccountFrozen as u32);
+    let result = env.redeem(&alice, &mint, &base_coin, 1_000_000).await;
+    assert_error(result, TokenError::AccountFrozen as u32);
+}
+
+#[tokio::test]
+async fn withdraw_fees_of_default_frozen_tokens() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let alice = env.create_user().await;
+    let bob = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.freezable = true;
+    args.default_frozen = true;
+    let mint = env.try_create_mint(&admin, &base_coin, args).await.unwrap();
+    env.send(
+        &[instructions::change_compliance_officer(
+            &admin.pubkey(),
+            &mint,
+            admin.pubkey(),
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    for wallet in [alice.pubkey(), bob.pubkey()] {
+        env.create_token_account(&wallet, &mint, &spl_token_2022::ID)
+            .await;
+        env.send(
+            &[instructions::thaw_account(
+                &admin.pubkey(),
+                &mint,
+                &mint_token_account(&wallet, &mint),
+            )],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+    }
+    env.fund_base_coin(&base_coin, &alice.pubkey(), 1_000_000)
+        .await;
+    env.issue(&alice, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    env.transfer_mint(&alice, &bob.pubkey(), &mint, 10_000_000)
+        .await;
+    env.create_token_account(&admin.pubkey(), &base_coin.mint, &base_coin.token_program)
+        .await;
+
+    // the authority's own token account is created frozen and thawed to receive the fees
+    let bob_account = mint_token_account(&bob.pubkey(), &mint);
+    env.withdraw_fees(&mint, &base_coin, &[bob_account])
+        .await
+        .unwrap();
+    assert_eq!(env.withheld_in_account(&bob_account).await, 0);
+    assert_eq!(
+        env.token_balance(&mint_token_account(&pda::authority(&mint).0, &mint))
+            .await,
+        0
+    );
+    // the issuance fee and the transfer fee converted to base coin, less the protocol's share
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&admin.pubkey()))
+            .await,
+        9_450
+    );
+}
+
+#[tokio::test]
+async fn freeze_rejects_other_officers_and_unfreezable_mints() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let officer = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.default_frozen = true;
+    let result = env.try_create_mint(&admin, &base_coin, args).await;
+    assert_error(
+        result.map(|_| ()),
+        CustomError::DefaultFrozenRequiresFreezable,
+    );
+
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.freezable = true;
+    let mint = env.try_create_mint(&admin, &base_coin, args).await.unwrap();
+    let unfreezable_mint = env.create_mint(&admin, &base_coin).await;
+    for mint in [mint, unfreezable_mint] {
+        env.send(
+            &[instructions::change_compliance_officer(
+                &admin.pubkey(),
+                &mint,
+                officer.pubkey(),
+            )],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+        env.create_token_account(&admin.pubkey(), &mint, &spl_token_2022::ID)
+            .await;
+    }
+
+    let result = env
+        .send(
+            &[instructions::freeze_account(
+                &admin.pubkey(),
+                &mint,
+                &mint_token_account(&admin.pubkey(), &mint),
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::IncorrectComplianceOfficer);
+
+    let result = env
+        .send(
+            &[instructions::freeze_account(
+                &officer.pubkey(),
+                &unfreezable_mint,
+                &mint_token_account(&admin.pubkey(), &unfreezable_mint),
+            )],
+            &[&officer],
+        )
+        .await;
+    assert_error(result, CustomError::MintIsNotFreezable);
+}
@@ -0,0 +1,571 @@
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self, extension::transfer_fee::instruction::harvest_withheld_tokens_to_mint,
+    },
+};
+use candy_wrapper::error::CustomError;
+use candy_wrapper_client::{
+    instructions::{self, ProtocolFeeClaim},
+    pda, FeePayout, FeeSplitRecipient,
+};
+
+use crate::setup::*;
+
+/// A mint whose admin, also its fee collector, issued 1_000_000 base coin and sent
+/// 10_000_000 tokens to a holder, who has 50_000 tokens of transfer fee withheld.
+struct Fixture {
+    env: Env,
+    admin: Keypair,
+    holder_account: Pubkey,
+    base_coin: BaseCoin,
+    mint: Pubkey,
+}
+
+impl Fixture {
+    async fn new(token_program: Pubkey) -> Self {
+        let mut env = Env::new().await;
+        let admin = env.create_user().await;
+        let holder = Pubkey::new_unique();
+        let base_coin = env.create_base_coin(token_program).await;
+        let mint = env.create_mint(&admin, &base_coin).await;
+        env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000_000)
+            .await;
+        env.issue(&admin, &mint, &base_coin, 1_000_000)
+            .await
+            .unwrap();
+        env.transfer_mint(&admin, &holder, &mint, 10_000_000).await;
+        let holder_account = mint_token_account(&holder, &mint);
+        assert_eq!(env.withheld_in_account(&holder_account).await, 50_000);
+        Self {
+            env,
+            admin,
+            holder_account,
+            base_coin,
+            mint,
+        }
+    }
+}
+
+async fn withdraw_in_base_coin(token_program: Pubkey) {
+    let Fixture {
+        mut env,
+        admin,
+        holder_account,
+        base_coin,
+        mint,
+    } = Fixture::new(token_program).await;
+
+    // anyone can move the withheld fees into the mint
+    env.send(
+        &[
+            harvest_withheld_tokens_to_mint(&spl_token_2022::ID, &mint, &[&holder_account])
+                .unwrap(),
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+    env.withdraw_fees(&mint, &base_coin, &[]).await.unwrap();
+
+    // 9_000 of the issuance fee and 450 for the 50_000 withheld tokens, burned at 0.01 base
+    // coin each, with 10% of both kept for the protocol
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&admin.pubkey()))
+            .await,
+        9_450
+    );
+    assert_eq!(env.fee_vault(&mint).await, 1_050);
+    assert_eq!(env.reserves(&mint, &base_coin).await, 989_500);
+    assert_eq!(env.supply(&mint).await, 98_950_000);
+    let authority = env.authority(&mint).await;
+    assert_eq!(authority.fees_collected, 9_450);
+    assert_eq!(authority.protocol_fees_accrued, 1_050);
+
+    // nothing is left to withdraw
+    env.withdraw_fees(&mint, &base_coin, &[]).await.unwrap();
+    assert_eq!(env.authority(&mint).await.fees_collected, 9_450);
+}
+
+#[tokio::test]
+async fn withdraw_in_base_coin_with_token_base_coin() {
+    withdraw_in_base_coin(spl_token::ID).await;
+}
+
+#[tokio::test]
+async fn withdraw_in_base_coin_with_token_2022_base_coin() {
+    withdraw_in_base_coin(spl_token_2022::ID).await;
+}
+
+async fn harvest_and_withdraw_in_mint(token_program: Pubkey) {
+    let Fixture {
+        mut env,
+        admin,
+        holder_account,
+        base_coin,
+        mint,
+    } = Fixture::new(token_program).await;
+    env.send(
+        &[instructions::change_fee_payout(
+            &admin.pubkey(),
+            &mint,
+            FeePayout::Mint,
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    env.withdraw_fees(&mint, &base_coin, &[holder_account])
+        .await
+        .unwrap();
+    assert_eq!(env.withheld_in_account(&holder_account).await, 0);
+
+    // 45_000 tokens less their own transfer fee, after the protocol's 5_000 were burned and
+    // settled in base coin
+    let admin_account = mint_token_account(&admin.pubkey(), &mint);
+    assert_eq!(env.token_balance(&admin_account).await, 89_044_775);
+    assert_eq!(env.withheld_in_account(&admin_account).await, 225);
+    assert_eq!(env.supply(&mint).await, 98_995_000);
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&admin.pubkey()))
+            .await,
+        9_000
+    );
+    assert_eq!(env.fee_vault(&mint).await, 1_050);
+    let authority = env.authority(&mint).await;
+    assert_eq!(authority.fees_collected, 9_450);
+    assert_eq!(authority.protocol_fees_accrued, 1_050);
+}
+
+#[tokio::test]
+async fn harvest_and_withdraw_in_mint_with_token_base_coin() {
+    harvest_and_withdraw_in_mint(spl_token::ID).await;
+}
+
+#[tokio::test]
+async fn harvest_and_withdraw_in_mint_with_token_2022_base_coin() {
+    harvest_and_withdraw_in_mint(spl_token_2022::ID).await;
+}
+
+#[tokio::test]
+async fn fees_account_for_transfer_fee_base_coin() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let holder = Pubkey::new_unique();
+    let base_coin = env.create_base_coin_with_transfer_fee(100, u64::MAX).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000_000)
+        .await;
+    env.issue(&admin, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    env.transfer_mint(&admin, &holder, &mint, 10_000_000).await;
+    let holder_account = mint_token_account(&holder, &mint);
+    env.withdraw_fees(&mint, &base_coin, &[holder_account])
+        .await
+        .unwrap();
+
+    // only what arrived is recorded, so the protocol's share can be claimed in full
+    let authority = env.authority(&mint).await;
+    assert_eq!(env.fee_vault(&mint).await, authority.protocol_fees_accrued);
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&admin.pubkey()))
+            .await,
+        authority.fees_collected
+    );
+
+    let payer = env.payer();
+    env.send(
+        &[instructions::claim_protocol_fees(
+            &payer.pubkey(),
+            &[ProtocolFeeClaim {
+                mint,
+                base_coin: base_coin.mint,
+                token_program_base_coin: base_coin.token_program,
+            }],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.fee_vault(&mint).await, 0);
+    assert_eq!(env.authority(&mint).await.protocol_fees_accrued, 0);
+}
+
+#[tokio::test]
+async fn withdraw_rejects_wrong_fee_collector_accounts() {
+    let Fixture {
+        mut env,
+        admin,
+        holder_account,
+        base_coin,
+        mint,
+    } = Fixture::new(spl_token::ID).await;
+    let payer = env.payer();
+
+    let other = env.create_user().await;
+    env.create_token_account(&other.pubkey(), &base_coin.mint, &base_coin.token_program)
+        .await;
+    let result = env
+        .send(
+            &[instructions::withdraw_fees(
+                &payer.pubkey(),
+                &mint,
+                &base_coin.mint,
+                &base_coin.token_program,
+                &other.pubkey(),
+                FeePayout::BaseCoin,
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, CustomError::IncorrectFeeCollector);
+
+    env.send(
+        &[instructions::change_fee_payout(
+            &admin.pubkey(),
+            &mint,
+            FeePayout::Mint,
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    // fees paid out in the mint only go to the fee collector's associated token account
+    let other_token_account = env
+        .create_other_token_account(&admin.pubkey(), &mint, &spl_token_2022::ID)
+        .await;
+    let ix = instructions::withdraw_fees(
+        &payer.pubkey(),
+        &mint,
+        &base_coin.mint,
+        &base_coin.token_program,
+        &admin.pubkey(),
+        FeePayout::Mint,
+    );
+    let result = env
+        .send(&[with_account(ix, 9, other_token_account)], &[])
+        .await;
+    assert_error(result, CustomError::IncorrectFeeCollector);
+
+    // paying out in the mint needs the fee collector's mint token account
+    let result = env
+        .send(
+            &[instructions::harvest_and_withdraw_fees(
+                &payer.pubkey(),
+                &mint,
+                &base_coin.mint,
+                &base_coin.token_program,
+                &admin.pubkey(),
+                FeePayout::BaseCoin,
+                &[holder_account],
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, CustomError::MissingFeeCollectorMintTokenAccount);
+}
+
+#[tokio::test]
+async fn fee_split_distributes_fees() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    let base_coin = env.create_base_coin(spl_token_2022::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000_000)
+        .await;
+
+    env.send(
+        &[instructions::set_fee_split(
+            &admin.pubkey(),
+            &mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+            vec![
+                FeeSplitRecipient {
+                    wallet: alice,
+                    basis_pts: 7_000,
+                },
+                FeeSplitRecipient {
+                    wallet: bob,
+                    basis_pts: 3_000,
+                },
+            ],
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    let fee_split = pda::fee_split(&mint).0;
+    assert_eq!(env.authority(&mint).await.fee_collector, fee_split);
+
+    // the split cannot be replaced directly as the fee collector, which would strand its revenue
+    let result = env
+        .send(
+            &[instructions::change_fee_collector(
+                &admin.pubkey(),
+                &mint,
+                admin.pubkey(),
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::FeeCollectorIsFeeSplit);
+
+    env.issue(&admin, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+
+    // fees only go to the split's associated token account, which `distribute` drains
+    let other_token_account = env
+        .create_other_token_account(&fee_split, &base_coin.mint, &base_coin.token_program)
+        .await;
+    let ix = instructions::withdraw_fees(
+        &admin.pubkey(),
+        &mint,
+        &base_coin.mint,
+        &base_coin.token_program,
+        &fee_split,
+        FeePayout::BaseCoin,
+    );
+    let result = env
+        .send(&[with_account(ix, 8, other_token_account)], &[&admin])
+        .await;
+    assert_error(result, CustomError::IncorrectFeeCollector);
+
+    env.withdraw_fees(&mint, &base_coin, &[]).await.unwrap();
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&fee_split))
+            .await,
+        9_000
+    );
+
+    env.create_token_account(&alice, &base_coin.mint, &base_coin.token_program)
+        .await;
+    env.create_token_account(&bob, &base_coin.mint, &base_coin.token_program)
+        .await;
+
+    // recipients must be passed completely and in order
+    let distribute = |recipients: &[Pubkey]| {
+        instructions::distribute(&mint, &base_coin.mint, &base_coin.token_program, recipients)
+    };
+    let result = env.send(&[distribute(&[alice])], &[]).await;
+    assert_error(result, CustomError::IncorrectFeeSplitRecipient);
+    let result = env.send(&[distribute(&[bob, alice])], &[]).await;
+    assert_error(result, CustomError::IncorrectFeeSplitRecipient);
+    // and are paid to their associated token accounts
+    let other_token_account = env
+        .create_other_token_account(&bob, &base_coin.mint, &base_coin.token_program)
+        .await;
+    let ix = distribute(&[alice, bob]);
+    let result = env
+        .send(&[with_account(ix, 5, other_token_account)], &[])
+        .await;
+    assert_error(result, CustomError::IncorrectFeeSplitRecipient);
+
+    env.send(&[distribute(&[alice, bob])], &[]).await.unwrap();
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&alice)).await,
+        6_300
+    );
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&bob)).await,
+        2_700
+    );
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&fee_split))
+            .await,
+        0
+    );
+}
+
+#[tokio::test]
+async fn set_fee_split_rejects_invalid_recipients() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+
+    let recipients = |count: usize, basis_pts: u16| {
+        (0..count)
+            .map(|_| FeeSplitRecipient {
+                wallet: Pubkey::new_unique(),
+                basis_pts,
+            })
+            .collect::<Vec<_>>()
+    };
+    let cases = [
+        (
+            recipients(0, 0),
+            CustomError::FeeSplitRecipientsCannotExceedMax,
+        ),
+        (
+            recipients(9, 1_000),
+            CustomError::FeeSplitRecipientsCannotExceedMax,
+        ),
+        (
+            recipients(2, 4_999),
+            CustomError::FeeSplitBasisPtsMustTotal10000,
+        ),
+        (
+            recipients(2, 5_001),
+            CustomError::FeeSplitBasisPtsMustTotal10000,
+        ),
+    ];
+    for (recipients, error) in cases {
+        let result = env
+            .send(
+                &[instructions::set_fee_split(
+                    &admin.pubkey(),
+                    &mint,
+                    &base_coin.mint,
+                    &base_coin.token_program,
+                    recipients,
+                )],
+                &[&admin],
+            )
+            .await;
+        assert_error(result, error);
+    }
+}
+
+#[tokio::test]
+async fn fee_split_rejects_mint_payout() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    let set_fee_split = instructions::set_fee_split(
+        &admin.pubkey(),
+        &mint,
+        &base_coin.mint,
+        &base_coin.token_program,
+        vec![FeeSplitRecipient {
+            wallet: Pubkey::new_unique(),
+            basis_pts: 10_000,
+        }],
+    );
+    let change_fee_payout =
+        |fee_payout| instructions::change_fee_payout(&admin.pubkey(), &mint, fee_payout);
+
+    // `distribute` cannot move fees paid out in the mint
+    env.send(&[change_fee_payout(FeePayout::Mint)], &[&admin])
+        .await
+        .unwrap();
+    let result = env
+        .send(std::slice::from_ref(&set_fee_split), &[&admin])
+        .await;
+    assert_error(result, CustomError::FeeSplitCannotPayFeesInMint);
+
+    env.send(&[change_fee_payout(FeePayout::BaseCoin)], &[&admin])
+        .await
+        .unwrap();
+    env.send(&[set_fee_split], &[&admin]).await.unwrap();
+    let result = env
+        .send(&[change_fee_payout(FeePayout::Mint)], &[&admin])
+        .await;
+    assert_error(result, CustomError::FeeSplitCannotPayFeesInMint);
+}
+
+#[tokio::test]
+async fn clear_fee_split_pays_out_and_replaces_fee_collector() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000_000)
+        .await;
+    let set_fee_split = instructions::set_fee_split(
+        &admin.pubkey(),
+        &mint,
+        &base_coin.mint,
+        &base_coin.token_program,
+        vec![
+            FeeSplitRecipient {
+                wallet: alice,
+                basis_pts: 3_333,
+            },
+            FeeSplitRecipient {
+                wallet: bob,
+                basis_pts: 6_667,
+            },
+        ],
+    );
+    env.send(std::slice::from_ref(&set_fee_split), &[&admin])
+        .await
+        .unwrap();
+    env.issue(&admin, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    env.withdraw_fees(&mint, &base_coin, &[]).await.unwrap();
+    let fee_split = pda::fee_split(&mint).0;
+    let fee_split_token_account = base_coin.token_account(&fee_split);
+    let balance = env.token_balance(&fee_split_token_account).await;
+    assert!(balance > 0);
+    env.create_token_account(&alice, &base_coin.mint, &base_coin.token_program)
+        .await;
+    env.create_token_account(&bob, &base_coin.mint, &base_coin.token_program)
+        .await;
+
+    let clear_fee_split = |signer: &Pubkey, new_fee_collector| {
+        instructions::clear_fee_split(
+            signer,
+            &mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+            &[alice, bob],
+            new_fee_collector,
+        )
+    };
+    let other = env.create_user().await;
+    let result = env
+        .send(
+            &[clear_fee_split(&other.pubkey(), other.pubkey())],
+            &[&other],
+        )
+        .await;
+    assert_error(result, CustomError::IncorrectUpdateAuthority);
+    let result = env
+        .send(&[clear_fee_split(&admin.pubkey(), fee_split)], &[&admin])
+        .await;
+    assert_error(result, CustomError::FeeCollectorIsFeeSplit);
+
+    env.send(
+        &[clear_fee_split(&admin.pubkey(), admin.pubkey())],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    // the last recipient takes the rounding remainder, emptying the split
+    let alice_share = balance * 3_333 / 10_000;
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&alice)).await,
+        alice_share
+    );
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&bob)).await,
+        balance - alice_share
+    );
+    assert!(env.account(&fee_split_token_account).await.is_none());
+    assert!(env.account(&fee_split).await.is_none());
+    assert_eq!(env.authority(&mint).await.fee_collector, admin.pubkey());
+
+    // the fee collector can be changed again, or a new split set
+    env.send(
+        &[instructions::change_fee_collector(
+            &admin.pubkey(),
+            &mint,
+            alice,
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    env.send(&[set_fee_split], &[&admin]).await.unwrap();
+    assert_eq!(env.authority(&mint).await.fee_collector, fee_split);
+}
@@ -0,0 +1,392 @@
+use std::cell::RefCell;
+
+use anchor_spl::{token::spl_token, token_2022::spl_token_2022};
+use candy_wrapper::error::CustomError;
+use candy_wrapper_client::{pda, TransferFeeArgs};
+use candy_wrapper_math::{calculate_base_coin_amount, calculate_fee, TransferFee};
+use proptest::prelude::*;
+use solana_program_test::BanksClientError;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::setup::*;
+
+const USERS: usize = 3;
+const USER_FUNDS: u64 = 10_000_000;
+
+#[derive(Clone, Debug)]
+struct Params {
+    token_2022_base_coin: bool,
+    // only charged by token 2022 base coins
+    base_coin_transfer_fee_basis_pts: u16,
+    mint_to_base_ratio: u16,
+    issuance_fee_basis_pts: u16,
+    redemption_fee_basis_pts: u16,
+    transfer_fee_basis_pts: u16,
+    max_transfer_fee: u64,
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    Issue { user: usize, amount: u64 },
+    // amounts of token balances are given in basis points of the balance
+    Transfer { from: usize, to: usize, share: u16 },
+    Redeem { user: usize, share: u16 },
+    Withdraw { harvest: bool },
+}
+
+fn params() -> impl Strategy<Value = Params> {
+    (
+        any::<bool>(),
+        prop_oneof![Just(0), 1..=500u16],
+        1..=1_000u16,
+        0..=100u16,
+        0..=100u16,
+        0..=1_000u16,
+        prop_oneof![Just(u64::MAX), 1..=10_000u64],
+    )
+        .prop_map(
+            |(
+                token_2022_base_coin,
+                base_coin_transfer_fee_basis_pts,
+                mint_to_base_ratio,
+                issuance_fee_basis_pts,
+                redemption_fee_basis_pts,
+                transfer_fee_basis_pts,
+                max_transfer_fee,
+            )| Params {
+                token_2022_base_coin,
+                base_coin_transfer_fee_basis_pts,
+                mint_to_base_ratio,
+                issuance_fee_basis_pts,
+                redemption_fee_basis_pts,
+                transfer_fee_basis_pts,
+                max_transfer_fee,
+            },
+        )
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => (0..USERS, 1..=2_000_000u64).prop_map(|(user, amount)| Op::Issue { user, amount }),
+        2 => (0..USERS, 0..USERS, 1..=10_000u16)
+            .prop_map(|(from, to, share)| Op::Transfer { from, to, share }),
+        2 => (0..USERS, 1..=10_000u16).prop_map(|(user, share)| Op::Redeem { user, share }),
+        1 => any::<bool>().prop_map(|harvest| Op::Withdraw { harvest }),
+    ]
+}
+
+// each case sends a few dozen transactions, so fewer cases than proptest's default run unless
+// PROPTEST_CASES asks for more
+fn config() -> ProptestConfig {
+    let cases = std::env::var("PROPTEST_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(32);
+    ProptestConfig::with_cases(cases)
+}
+
+/// Whether `result` succeeded. Any failure other than `allowed` fails the case.
+fn succeeded(result: Result<(), BanksClientError>, allowed: &[CustomError]) -> bool {
+    let Err(err) = result else {
+        return true;
+    };
+    let err = err.unwrap();
+    let allowed = allowed.iter().any(|error| {
+        err == solana_sdk::transaction::TransactionError::InstructionError(
+            0,
+            solana_sdk::instruction::InstructionError::Custom(
+                anchor_lang::error::ERROR_CODE_OFFSET + *error as u32,
+            ),
+        )
+    });
+    assert!(allowed, "unexpected error {err:?}");
+    false
+}
+
+#[derive(Default)]
+struct UserLedger {
+    // base coin moved into the vault on the user's issuances, net of issuance fees
+    deposited: u64,
+    // base coin the user received on redemptions, net of redemption fees
+    redeemed: u64,
+    received_tokens: bool,
+}
+
+struct Harness<'a> {
+    env: &'a mut Env,
+    params: Params,
+    base_coin: BaseCoin,
+    mint: Pubkey,
+    users: Vec<Keypair>,
+    ledgers: Vec<UserLedger>,
+    // operations that may round in favour of every holder, issuances and redemptions
+    // credited to the user who made them
+    rounding_ops: usize,
+    rounding_ops_by_user: Vec<usize>,
+}
+
+impl<'a> Harness<'a> {
+    async fn new(env: &'a mut Env, params: Params) -> Self {
+        let admin = env.create_user().await;
+        let base_coin = if !params.token_2022_base_coin {
+            env.create_base_coin(spl_token::ID).await
+        } else if params.base_coin_transfer_fee_basis_pts > 0 {
+            env.create_base_coin_with_transfer_fee(
+                params.base_coin_transfer_fee_basis_pts,
+                u64::MAX,
+            )
+            .await
+        } else {
+            env.create_base_coin(spl_token_2022::ID).await
+        };
+        let mut args = default_args(&admin.pubkey(), &base_coin);
+        args.mint_to_base_ratio = params.mint_to_base_ratio;
+        args.issuance_fee_basis_pts = params.issuance_fee_basis_pts;
+        args.redemption_fee_basis_pts = params.redemption_fee_basis_pts;
+        args.transfer_fee_args = TransferFeeArgs {
+            fee_basis_pts: params.transfer_fee_basis_pts,
+            max_fee: params.max_transfer_fee,
+        };
+        let mint = env.try_create_mint(&admin, &base_coin, args).await.unwrap();
+        // the admin collects the fees in base coin
+        env.create_token_account(&admin.pubkey(), &base_coin.mint, &base_coin.token_program)
+            .await;
+
+        let mut users = vec![];
+        for _ in 0..USERS {
+            let user = env.create_user().await;
+            env.fund_base_coin(&base_coin, &user.pubkey(), USER_FUNDS)
+                .await;
+            env.create_token_account(&user.pubkey(), &mint, &spl_token_2022::ID)
+                .await;
+            users.push(user);
+        }
+
+        Self {
+            env,
+            params,
+            base_coin,
+            mint,
+            users,
+            ledgers: (0..USERS).map(|_| UserLedger::default()).collect(),
+            rounding_ops: 0,
+            rounding_ops_by_user: vec![0; USERS],
+        }
+    }
+
+    async fn base_balance(&mut self, user: usize) -> u64 {
+        let account = self.base_coin.token_account(&self.users[user].pubkey());
+        self.env.token_balance(&account).await
+    }
+
+    async fn mint_balance(&mut self, user: usize) -> u64 {
+        let account = mint_token_account(&self.users[user].pubkey(), &self.mint);
+        self.env.token_balance(&account).await
+    }
+
+    fn holder_accounts(&self) -> Vec<Pubkey> {
+        self.users
+            .iter()
+            .map(|user| mint_token_account(&user.pubkey(), &self.mint))
+            .collect()
+    }
+
+    async fn apply(&mut self, op: &Op) {
+        match *op {
+            Op::Issue { user, amount } => {
+                let reserves = self.env.reserves(&self.mint, &self.base_coin).await;
+                let user_key = self.users[user].insecure_clone();
+                let result = self
+                    .env
+                    .issue(&user_key, &self.mint, &self.base_coin, amount)
+                    .await;
+                if succeeded(
+                    result,
+                    &[CustomError::InsufficientAmount, CustomError::ZeroOutput],
+                ) {
+                    let deposited = self.env.reserves(&self.mint, &self.base_coin).await - reserves;
+                    self.ledgers[user].deposited += deposited;
+                    self.count_rounding(user);
+                }
+            }
+            Op::Transfer { from, to, share } => {
+                let amount = self.mint_balance(from).await * share as u64 / 10_000;
+                if from == to || amount == 0 {
+                    return;
+                }
+                let from_key = self.users[from].insecure_clone();
+                let to_key = self.users[to].pubkey();
+                self.env
+                    .transfer_mint(&from_key, &to_key, &self.mint, amount)
+                    .await;
+                self.ledgers[to].received_tokens = true;
+            }
+            Op::Redeem { user, share } => {
+                let amount = self.mint_balance(user).await * share as u64 / 10_000;
+                if amount == 0 {
+                    return;
+                }
+                self.redeem(user, amount, &[CustomError::ZeroOutput]).await;
+            }
+            Op::Withdraw { harvest } => self.withdraw(harvest).await,
+        }
+    }
+
+    async fn withdraw(&mut self, harvest: bool) {
+        // the vault is created on the first issuance, no fees can accrue before
+        let reserves = pda::reserves(
+            &self.mint,
+            &self.base_coin.mint,
+            &self.base_coin.token_program,
+        );
+        if self.env.account(&reserves).await.is_none() {
+            return;
+        }
+        let holders = if harvest {
+            self.holder_accounts()
+        } else {
+            vec![]
+        };
+        self.env
+            .withdraw_fees(&self.mint, &self.base_coin, &holders)
+            .await
+            .unwrap();
+        self.rounding_ops += 1;
+    }
+
+    async fn redeem(&mut self, user: usize, amount: u64, allowed: &[CustomError]) -> bool {
+        let balance = self.base_balance(user).await;
+        let user_key = self.users[user].insecure_clone();
+        let result = self
+            .env
+            .redeem(&user_key, &self.mint, &self.base_coin, amount)
+            .await;
+        if !succeeded(result, allowed) {
+            return false;
+        }
+        self.ledgers[user].redeemed += self.base_balance(user).await - balance;
+        self.count_rounding(user);
+        true
+    }
+
+    fn base_coin_transfer_fee(&self) -> TransferFee {
+        TransferFee {
+            epoch: 0,
+            maximum_fee: u64::MAX,
+            transfer_fee_basis_pts: if self.params.token_2022_base_coin {
+                self.params.base_coin_transfer_fee_basis_pts
+            } else {
+                0
+            },
+        }
+    }
+
+    fn count_rounding(&mut self, user: usize) {
+        self.rounding_ops += 1;
+        self.rounding_ops_by_user[user] += 1;
+    }
+
+    async fn assert_collateralised(&mut self) {
+        let reserves = self.env.reserves(&self.mint, &self.base_coin).await;
+        let supply = self.env.supply(&self.mint).await;
+        assert!(
+            reserves as u128 * self.params.mint_to_base_ratio as u128 >= supply as u128,
+            "reserves of {reserves} do not cover a supply of {supply} at the peg"
+        );
+    }
+
+    fn assert_no_user_profits(&self) {
+        let deposited: u64 = self.ledgers.iter().map(|ledger| ledger.deposited).sum();
+        let redeemed: u64 = self.ledgers.iter().map(|ledger| ledger.redeemed).sum();
+        assert!(redeemed <= deposited);
+
+        for (user, ledger) in self.ledgers.iter().enumerate() {
+            if ledger.received_tokens {
+                continue;
+            }
+            // every other operation can round at most one base unit in the holders' favour
+            let rounding = (self.rounding_ops - self.rounding_ops_by_user[user]) as u64;
+            assert!(
+                ledger.redeemed <= ledger.deposited + rounding,
+                "user {user} redeemed {} for {} deposited",
+                ledger.redeemed,
+                ledger.deposited
+            );
+        }
+    }
+
+    // harvests every withheld fee so the whole supply sits in the users' balances, then
+    // redeems the users from the smallest to the largest balance. Only redemptions worth
+    // nothing after the fees may fail, so the last redeemer can always exit.
+    async fn exit_all(&mut self) {
+        self.withdraw(true).await;
+
+        let mut balances = vec![];
+        for user in 0..USERS {
+            balances.push((self.mint_balance(user).await, user));
+        }
+        balances.retain(|(balance, _)| *balance > 0);
+        balances.sort();
+        assert_eq!(
+            self.env.supply(&self.mint).await,
+            balances.iter().map(|(balance, _)| balance).sum::<u64>()
+        );
+
+        let mut dust_left = false;
+        for (balance, user) in balances {
+            let reserves = self.env.reserves(&self.mint, &self.base_coin).await;
+            let supply = self.env.supply(&self.mint).await;
+            let value = calculate_base_coin_amount(balance, reserves, supply).unwrap();
+            let fee = calculate_fee(value, self.params.redemption_fee_basis_pts).unwrap();
+            let received = self
+                .base_coin_transfer_fee()
+                .calculate_post_fee_amount(value - fee)
+                .unwrap();
+            if received > 0 {
+                self.redeem(user, balance, &[]).await;
+            } else {
+                dust_left |= !self.redeem(user, balance, &[CustomError::ZeroOutput]).await;
+            }
+        }
+        if !dust_left {
+            assert_eq!(self.env.supply(&self.mint).await, 0);
+            assert_eq!(self.env.reserves(&self.mint, &self.base_coin).await, 0);
+        }
+    }
+}
+
+async fn run(env: &mut Env, params: Params, ops: Vec<Op>) {
+    let mut harness = Harness::new(env, params).await;
+    for op in &ops {
+        harness.apply(op).await;
+        harness.assert_collateralised().await;
+    }
+    harness.exit_all().await;
+    harness.assert_collateralised().await;
+    harness.assert_no_user_profits();
+}
+
+thread_local! {
+    // runtimes are not torn down completely when dropped, so all cases share one and work on
+    // their own mints
+    static RUNTIME: RefCell<Option<(Runtime, Env)>> = const { RefCell::new(None) };
+}
+
+proptest! {
+    #![proptest_config(config())]
+
+    #[test]
+    fn random_operations_keep_invariants(
+        params in params(),
+        ops in prop::collection::vec(op(), 1..24),
+    ) {
+        RUNTIME.with_borrow_mut(|runtime| {
+            let (runtime, env) = runtime.get_or_insert_with(|| {
+                let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+                let env = runtime.block_on(Env::new());
+                (runtime, env)
+            });
+            runtime.block_on(run(env, params, ops));
+        });
+    }
+}
@@ -0,0 +1,176 @@
+use anchor_spl::token::spl_token;
+use candy_wrapper::{error::CustomError, state::MAX_MERCHANTS};
+use candy_wrapper_client::{instructions, FeePayout, TransferHookArgs};
+
+use crate::setup::*;
+
+async fn create_gift_card(env: &mut Env, admin: &Keypair, base_coin: &BaseCoin) -> Pubkey {
+    let mut args = default_args(&admin.pubkey(), base_coin);
+    args.gift_card = true;
+    env.try_create_mint(admin, base_coin, args).await.unwrap()
+}
+
+#[tokio::test]
+async fn gift_cards_only_move_through_merchants() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let alice = env.create_user().await;
+    let shop = env.create_user().await;
+    let bob = Pubkey::new_unique();
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = create_gift_card(&mut env, &admin, &base_coin).await;
+    assert_eq!(env.authority(&mint).await.gift_card, 1);
+
+    // issuance is unaffected, but holders cannot pass the card on to each other
+    env.fund_base_coin(&base_coin, &alice.pubkey(), 1_000_000)
+        .await;
+    env.issue(&alice, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    let result = env.try_transfer_mint(&alice, &bob, &mint, 1_000_000).await;
+    assert_error(result, CustomError::TransferRequiresMerchant);
+
+    // registered merchants accept the card and can pass it on themselves
+    env.send(
+        &[instructions::add_merchant(
+            &admin.pubkey(),
+            &mint,
+            shop.pubkey(),
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    env.transfer_mint(&alice, &shop.pubkey(), &mint, 10_000_000)
+        .await;
+    env.transfer_mint(&shop, &bob, &mint, 1_000_000).await;
+
+    env.send(
+        &[instructions::remove_merchant(
+            &admin.pubkey(),
+            &mint,
+            shop.pubkey(),
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    let result = env
+        .try_transfer_mint(&alice, &shop.pubkey(), &mint, 1_000_000)
+        .await;
+    assert_error(result, CustomError::TransferRequiresMerchant);
+
+    // redemption is unaffected as well
+    let balance = env
+        .token_balance(&mint_token_account(&alice.pubkey(), &mint))
+        .await;
+    env.redeem(&alice, &mint, &base_coin, balance)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn merchant_registry_rejects_invalid_changes() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let intruder = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = create_gift_card(&mut env, &admin, &base_coin).await;
+    let merchants: Vec<Pubkey> = (0..MAX_MERCHANTS).map(|_| Pubkey::new_unique()).collect();
+
+    let result = env
+        .send(
+            &[instructions::add_merchant(
+                &intruder.pubkey(),
+                &mint,
+                merchants[0],
+            )],
+            &[&intruder],
+        )
+        .await;
+    assert_error(result, CustomError::IncorrectUpdateAuthority);
+    let result = env
+        .send(
+            &[instructions::remove_merchant(
+                &admin.pubkey(),
+                &mint,
+                merchants[0],
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::MerchantNotRegistered);
+
+    for batch in merchants.chunks(16) {
+        let ixs: Vec<_> = batch
+            .iter()
+            .map(|merchant| instructions::add_merchant(&admin.pubkey(), &mint, *merchant))
+            .collect();
+        env.send(&ixs, &[&admin]).await.unwrap();
+    }
+    let result = env
+        .send(
+            &[instructions::add_merchant(
+                &admin.pubkey(),
+                &mint,
+                merchants[0],
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::MerchantAlreadyRegistered);
+    let result = env
+        .send(
+            &[instructions::add_merchant(
+                &admin.pubkey(),
+                &mint,
+                Pubkey::new_unique(),
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::MerchantsCannotExceedMax);
+
+    // paying fees out in the mint would invoke the program as its own transfer hook
+    let result = env
+        .send(
+            &[instructions::change_fee_payout(
+                &admin.pubkey(),
+                &mint,
+                FeePayout::Mint,
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::TransferHookCannotPayFeesInMint);
+}
+
+#[tokio::test]
+async fn create_gift_card_rejects_invalid_args() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.gift_card = true;
+    args.transfer_hook_args = Some(TransferHookArgs {
+        program_id: TRANSFER_HOOK_ID,
+        authority: admin.pubkey(),
+    });
+    let result = env.try_create_mint(&admin, &base_coin, args).await;
+    assert_error(result.map(|_| ()), CustomError::TransferHookTakenByProgram);
+
+    // without the merchant registry and the transfer hook's account list
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.gift_card = true;
+    let mint = Keypair::new();
+    let ix = instructions::create_mint(
+        &admin.pubkey(),
+        &mint.pubkey(),
+        &base_coin.token_program,
+        args,
+    );
+    let ix = with_account(with_account(ix, 7, candy_wrapper::ID), 8, candy_wrapper::ID);
+    let result = env.send(&[ix], &[&admin, &mint]).await;
+    assert_error(result, CustomError::MissingTransferHookAccounts);
+}
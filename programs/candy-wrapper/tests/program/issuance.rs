@@ -0,0 +1,411 @@
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022::{self, extension::ExtensionType, state::Mint},
+};
+use candy_wrapper::{error::CustomError, instructions::mint_extensions};
+use candy_wrapper_client::{instructions, pda};
+use solana_sdk::{instruction::Instruction, rent::Rent, system_instruction};
+
+use crate::setup::*;
+
+async fn issue_and_redeem(token_program: Pubkey) {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let alice = env.create_user().await;
+    let bob = env.create_user().await;
+    let base_coin = env.create_base_coin(token_program).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &alice.pubkey(), 1_000_000)
+        .await;
+    env.fund_base_coin(&base_coin, &bob.pubkey(), 500_000).await;
+
+    // the first issuance is priced at the mint to base ratio
+    env.issue(&alice, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    assert_eq!(
+        env.token_balance(&mint_token_account(&alice.pubkey(), &mint))
+            .await,
+        99_000_000
+    );
+    assert_eq!(env.reserves(&mint, &base_coin).await, 990_000);
+    assert_eq!(env.fee_vault(&mint).await, 10_000);
+
+    // later ones at the backing of the outstanding supply
+    env.issue(&bob, &mint, &base_coin, 500_000).await.unwrap();
+    assert_eq!(
+        env.token_balance(&mint_token_account(&bob.pubkey(), &mint))
+            .await,
+        49_500_000
+    );
+    assert_eq!(env.supply(&mint).await, 148_500_000);
+    assert_eq!(env.reserves(&mint, &base_coin).await, 1_485_000);
+
+    env.redeem(&bob, &mint, &base_coin, 49_500_000)
+        .await
+        .unwrap();
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&bob.pubkey()))
+            .await,
+        490_050
+    );
+
+    env.redeem(&alice, &mint, &base_coin, 99_000_000)
+        .await
+        .unwrap();
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&alice.pubkey()))
+            .await,
+        980_100
+    );
+
+    assert_eq!(env.supply(&mint).await, 0);
+    assert_eq!(env.reserves(&mint, &base_coin).await, 0);
+    assert_eq!(env.fee_vault(&mint).await, 29_850);
+}
+
+#[tokio::test]
+async fn issue_and_redeem_with_token_base_coin() {
+    issue_and_redeem(spl_token::ID).await;
+}
+
+#[tokio::test]
+async fn issue_and_redeem_with_token_2022_base_coin() {
+    issue_and_redeem(spl_token_2022::ID).await;
+}
+
+#[tokio::test]
+async fn issue_and_redeem_with_transfer_fee_base_coin() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let alice = env.create_user().await;
+    // 1% of every base coin transfer is withheld
+    let base_coin = env.create_base_coin_with_transfer_fee(100, u64::MAX).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &alice.pubkey(), 1_000_000)
+        .await;
+
+    // tokens are only issued for the 980_100 of the 990_000 that arrive in the vault
+    env.issue(&alice, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    let alice_account = mint_token_account(&alice.pubkey(), &mint);
+    assert_eq!(env.token_balance(&alice_account).await, 98_010_000);
+    assert_eq!(env.reserves(&mint, &base_coin).await, 980_100);
+    assert_eq!(env.fee_vault(&mint).await, 9_900);
+
+    // the vault releases 980_100, of which the redemption fee and both transfer fees are kept
+    env.redeem(&alice, &mint, &base_coin, 98_010_000)
+        .await
+        .unwrap();
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&alice.pubkey()))
+            .await,
+        960_596
+    );
+    assert_eq!(env.reserves(&mint, &base_coin).await, 0);
+    assert_eq!(env.fee_vault(&mint).await, 19_602);
+}
+
+#[tokio::test]
+async fn create_mint_rejects_invalid_args() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.issuance_fee_basis_pts = 101;
+    let result = env.try_create_mint(&admin, &base_coin, args).await;
+    assert_error(
+        result.map(|_| ()),
+        CustomError::IssuanceFeeBasisPtsCannotExceed100,
+    );
+
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.redemption_fee_basis_pts = 101;
+    let result = env.try_create_mint(&admin, &base_coin, args).await;
+    assert_error(
+        result.map(|_| ()),
+        CustomError::RedemptionFeeBasisPtsCannotExceed100,
+    );
+
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.mint_to_base_ratio = 0;
+    let result = env.try_create_mint(&admin, &base_coin, args).await;
+    assert_error(result.map(|_| ()), CustomError::MintRatioCannotBeZero);
+}
+
+#[tokio::test]
+async fn create_mint_rejects_base_coin_not_in_args() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let other_base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = Keypair::new();
+
+    // accounts for the other base coin, args for the expected one
+    let mut ix = instructions::create_mint(
+        &admin.pubkey(),
+        &mint.pubkey(),
+        &spl_token::ID,
+        default_args(&admin.pubkey(), &other_base_coin),
+    );
+    ix.data = instructions::create_mint(
+        &admin.pubkey(),
+        &mint.pubkey(),
+        &spl_token::ID,
+        default_args(&admin.pubkey(), &base_coin),
+    )
+    .data;
+    let result = env.send(&[ix], &[&admin, &mint]).await;
+    assert_error(result, CustomError::UnauthorizedBaseCoin);
+}
+
+#[tokio::test]
+async fn create_mint_at_seeded_address() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let seed = [7; 32];
+    let mint = pda::mint(&admin.pubkey(), &seed).0;
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.mint_seed = Some(seed);
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&mint_extensions(&args)).unwrap();
+
+    // a mint without a seed must sign
+    let mint_keypair = Keypair::new();
+    let mut ix = instructions::create_mint(
+        &admin.pubkey(),
+        &mint_keypair.pubkey(),
+        &base_coin.token_program,
+        default_args(&admin.pubkey(), &base_coin),
+    );
+    ix.accounts[1].is_signer = false;
+    let result = env.send(&[ix], &[&admin]).await;
+    assert_error(result, CustomError::MintIsNotSigner);
+
+    // and one with a seed must match the payer and the seed
+    let other_mint = pda::mint(&admin.pubkey(), &[8; 32]).0;
+    let ix =
+        instructions::create_mint(&admin.pubkey(), &other_mint, &base_coin.token_program, args);
+    let result = env.send(&[ix], &[&admin]).await;
+    assert_error(result, CustomError::IncorrectMintSeed);
+
+    // lamports sent to the address beforehand do not block creating the mint there
+    env.send(
+        &[system_instruction::transfer(
+            &admin.pubkey(),
+            &mint,
+            Rent::default().minimum_balance(0),
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.mint_seed = Some(seed);
+    let ix = instructions::create_mint(&admin.pubkey(), &mint, &base_coin.token_program, args);
+    env.send(&[ix], &[&admin]).await.unwrap();
+    let account = env.account(&mint).await.unwrap();
+    assert_eq!(account.owner, spl_token_2022::ID);
+    assert_eq!(account.data.len(), space);
+    assert_eq!(account.lamports, Rent::default().minimum_balance(space));
+    assert_eq!(env.authority(&mint).await.mint, mint);
+
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000).await;
+    env.issue(&admin, &mint, &base_coin, 1_000).await.unwrap();
+    assert_eq!(env.supply(&mint).await, 99_000);
+}
+
+#[tokio::test]
+async fn issue_rejects_insufficient_base_coin() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000).await;
+
+    let result = env.issue(&admin, &mint, &base_coin, 1_001).await;
+    assert_error(result, CustomError::InsufficientAmount);
+}
+
+#[tokio::test]
+async fn redeem_rejects_insufficient_tokens() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000).await;
+    env.issue(&admin, &mint, &base_coin, 1_000).await.unwrap();
+
+    let result = env.redeem(&admin, &mint, &base_coin, 99_001).await;
+    assert_error(result, CustomError::InsufficientAmount);
+}
+
+#[tokio::test]
+async fn issue_and_redeem_reject_zero_output() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token_2022::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000).await;
+
+    // the fee rounds up to the whole deposit
+    let result = env.issue(&admin, &mint, &base_coin, 1).await;
+    assert_error(result, CustomError::ZeroOutput);
+
+    // one token is worth less than a base unit
+    env.issue(&admin, &mint, &base_coin, 999).await.unwrap();
+    let result = env.redeem(&admin, &mint, &base_coin, 1).await;
+    assert_error(result, CustomError::ZeroOutput);
+}
+
+#[tokio::test]
+async fn issue_rejects_overflowing_amount() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    let amount = u64::MAX / 50;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), amount)
+        .await;
+
+    let result = env.issue(&admin, &mint, &base_coin, amount).await;
+    assert_error(result, CustomError::MathOverflow);
+}
+
+#[tokio::test]
+async fn issue_and_redeem_reject_other_mint() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    let other_mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 2_000).await;
+    env.issue(&admin, &mint, &base_coin, 1_000).await.unwrap();
+    env.issue(&admin, &other_mint, &base_coin, 1_000)
+        .await
+        .unwrap();
+
+    // the other mint's accounts against this mint's authority and vault
+    let authority = pda::authority(&mint).0;
+    let reserves = pda::reserves(&mint, &base_coin.mint, &spl_token::ID);
+    let ix = instructions::issue_mint(
+        &admin.pubkey(),
+        &other_mint,
+        &base_coin.mint,
+        &base_coin.token_program,
+        1_000,
+    );
+    let result = env
+        .send(
+            &[with_account(with_account(ix, 5, reserves), 6, authority)],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::IncorrectMint);
+
+    let ix = instructions::redeem_basecoin(
+        &admin.pubkey(),
+        &other_mint,
+        &base_coin.mint,
+        &base_coin.token_program,
+        1_000,
+    );
+    let result = env
+        .send(
+            &[with_account(with_account(ix, 5, reserves), 6, authority)],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::IncorrectMint);
+}
+
+#[tokio::test]
+async fn issue_and_redeem_reject_other_base_coin() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let other_base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000).await;
+    env.fund_base_coin(&other_base_coin, &admin.pubkey(), 1_000)
+        .await;
+    env.issue(&admin, &mint, &base_coin, 1_000).await.unwrap();
+
+    let result = env.issue(&admin, &mint, &other_base_coin, 1_000).await;
+    assert_error(result, CustomError::UnauthorizedBaseCoin);
+
+    // the vault of the other base coin was never created
+    let ix = instructions::redeem_basecoin(
+        &admin.pubkey(),
+        &mint,
+        &other_base_coin.mint,
+        &other_base_coin.token_program,
+        1_000,
+    );
+    let reserves = pda::reserves(&mint, &base_coin.mint, &spl_token::ID);
+    let result = env.send(&[with_account(ix, 5, reserves)], &[&admin]).await;
+    assert_error(result, CustomError::UnauthorizedBaseCoin);
+}
+
+// issuance and redemption of one mint still serialize on the mint, the reserves and the fee vault,
+// but no longer on the authority or the fee collector's token account
+fn assert_write_locks(ix: &Instruction, expected: &[Pubkey]) {
+    let mut writable: Vec<Pubkey> = ix
+        .accounts
+        .iter()
+        .filter(|meta| meta.is_writable)
+        .map(|meta| meta.pubkey)
+        .collect();
+    let mut expected = expected.to_vec();
+    writable.sort();
+    expected.sort();
+    assert_eq!(writable, expected);
+}
+
+#[tokio::test]
+async fn issue_and_redeem_leave_the_authority_unlocked() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let alice = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &alice.pubkey(), 1_000_000)
+        .await;
+    env.issue(&alice, &mint, &base_coin, 1_000).await.unwrap();
+
+    let shared = [
+        mint,
+        pda::reserves(&mint, &base_coin.mint, &base_coin.token_program),
+        pda::fee_vault(&mint).0,
+    ];
+    let payer = [
+        alice.pubkey(),
+        mint_token_account(&alice.pubkey(), &mint),
+        base_coin.token_account(&alice.pubkey()),
+    ];
+    let issue = instructions::issue_mint(
+        &alice.pubkey(),
+        &mint,
+        &base_coin.mint,
+        &base_coin.token_program,
+        500_000,
+    );
+    let redeem = instructions::redeem_basecoin(
+        &alice.pubkey(),
+        &mint,
+        &base_coin.mint,
+        &base_coin.token_program,
+        1_000_000,
+    );
+    assert_write_locks(&issue, &[shared, payer].concat());
+    assert_write_locks(&redeem, &[shared, payer].concat());
+
+    // the native build only meters the token program CPIs, measured at 18,107 units to issue
+    // and 18,413 to redeem
+    let issue_units = env.compute_units(&[issue], &[&alice]).await;
+    let redeem_units = env.compute_units(&[redeem], &[&alice]).await;
+    assert!(issue_units <= 20_000, "issue used {issue_units} units");
+    assert!(redeem_units <= 20_000, "redeem used {redeem_units} units");
+}
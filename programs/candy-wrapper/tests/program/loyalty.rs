@@ -0,0 +1,292 @@
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use candy_wrapper_client::{pda, TransferHookArgs};
+use loyalty_hook::{accounts, error::LoyaltyError, instruction, state::Points};
+use solana_sdk::instruction::Instruction;
+
+use crate::setup::*;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: loyalty_hook::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn loyalty_config(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"loyalty_config", mint.as_ref()], &loyalty_hook::ID).0
+}
+
+fn merchant(mint: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"merchant", mint.as_ref(), wallet.as_ref()],
+        &loyalty_hook::ID,
+    )
+    .0
+}
+
+fn points(mint: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"points", mint.as_ref(), wallet.as_ref()],
+        &loyalty_hook::ID,
+    )
+    .0
+}
+
+fn initialize(admin: &Pubkey, mint: &Pubkey, points_basis_pts: u16) -> Instruction {
+    build(
+        accounts::InitializeCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+            mint: *mint,
+            extra_account_meta_list: Pubkey::find_program_address(
+                &[b"extra-account-metas", mint.as_ref()],
+                &loyalty_hook::ID,
+            )
+            .0,
+            config: loyalty_config(mint),
+            system_program: solana_sdk::system_program::ID,
+        },
+        instruction::Initialize { points_basis_pts },
+    )
+}
+
+fn register_merchant(admin: &Pubkey, mint: &Pubkey, wallet: Pubkey) -> Instruction {
+    build(
+        accounts::RegisterMerchantCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+            mint: *mint,
+            config: loyalty_config(mint),
+            merchant: merchant(mint, &wallet),
+            system_program: solana_sdk::system_program::ID,
+        },
+        instruction::RegisterMerchant { wallet },
+    )
+}
+
+fn remove_merchant(admin: &Pubkey, mint: &Pubkey, wallet: &Pubkey) -> Instruction {
+    build(
+        accounts::RemoveMerchantCtx {
+            payer: *admin,
+            authority: pda::authority(mint).0,
+            mint: *mint,
+            merchant: merchant(mint, wallet),
+        },
+        instruction::RemoveMerchant {},
+    )
+}
+
+fn open_points(payer: &Pubkey, mint: &Pubkey, wallet: Pubkey) -> Instruction {
+    build(
+        accounts::OpenPointsCtx {
+            payer: *payer,
+            mint: *mint,
+            config: loyalty_config(mint),
+            points: points(mint, &wallet),
+            system_program: solana_sdk::system_program::ID,
+        },
+        instruction::OpenPoints { wallet },
+    )
+}
+
+fn redeem_points(
+    wallet: &Pubkey,
+    merchant_wallet: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::RedeemPointsCtx {
+            wallet: *wallet,
+            merchant_wallet: *merchant_wallet,
+            mint: *mint,
+            merchant: merchant(mint, merchant_wallet),
+            points: points(mint, wallet),
+        },
+        instruction::RedeemPoints { amount },
+    )
+}
+
+async fn points_of(env: &mut Env, mint: &Pubkey, wallet: &Pubkey) -> Points {
+    let account = env.account(&points(mint, wallet)).await.unwrap();
+    Points::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+// a mint whose transfers are hooked into the loyalty program, and its base coin
+async fn create_loyalty_mint(env: &mut Env, admin: &Keypair) -> (Pubkey, BaseCoin) {
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.transfer_hook_args = Some(TransferHookArgs {
+        program_id: loyalty_hook::ID,
+        authority: admin.pubkey(),
+    });
+    let mint = env.try_create_mint(admin, &base_coin, args).await.unwrap();
+    (mint, base_coin)
+}
+
+#[tokio::test]
+async fn purchases_at_merchants_earn_points() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let alice = env.create_user().await;
+    let shop = env.create_user().await;
+    let bob = Pubkey::new_unique();
+    let (mint, base_coin) = create_loyalty_mint(&mut env, &admin).await;
+
+    // a tenth of a point per token
+    env.send(
+        &[
+            initialize(&admin.pubkey(), &mint, 1_000),
+            register_merchant(&admin.pubkey(), &mint, shop.pubkey()),
+        ],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    env.fund_base_coin(&base_coin, &alice.pubkey(), 1_000_000)
+        .await;
+    env.issue(&alice, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    env.send(
+        &[open_points(&alice.pubkey(), &mint, alice.pubkey())],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+
+    // only transfers to merchants count
+    env.transfer_mint(&alice, &bob, &mint, 1_000_000).await;
+    assert_eq!(points_of(&mut env, &mint, &alice.pubkey()).await.balance, 0);
+    env.transfer_mint(&alice, &shop.pubkey(), &mint, 10_000_000)
+        .await;
+    assert_eq!(
+        points_of(&mut env, &mint, &alice.pubkey()).await.balance,
+        1_000_000
+    );
+
+    // the merchant signs off on rewards paid for with points
+    env.send(
+        &[redeem_points(
+            &alice.pubkey(),
+            &shop.pubkey(),
+            &mint,
+            400_000,
+        )],
+        &[&alice, &shop],
+    )
+    .await
+    .unwrap();
+    let alice_points = points_of(&mut env, &mint, &alice.pubkey()).await;
+    assert_eq!(alice_points.balance, 600_000);
+    assert_eq!(alice_points.earned, 1_000_000);
+    let result = env
+        .send(
+            &[redeem_points(
+                &alice.pubkey(),
+                &shop.pubkey(),
+                &mint,
+                600_001,
+            )],
+            &[&alice, &shop],
+        )
+        .await;
+    assert_error(result, LoyaltyError::InsufficientPoints);
+
+    // once removed, the shop no longer earns its customers points
+    env.send(
+        &[remove_merchant(&admin.pubkey(), &mint, &shop.pubkey())],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    env.transfer_mint(&alice, &shop.pubkey(), &mint, 10_000_000)
+        .await;
+    assert_eq!(
+        points_of(&mut env, &mint, &alice.pubkey()).await.balance,
+        600_000
+    );
+}
+
+#[tokio::test]
+async fn initialize_rejects_other_mints_and_signers() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let intruder = env.create_user().await;
+    let (mint, base_coin) = create_loyalty_mint(&mut env, &admin).await;
+    let unhooked_mint = env.create_mint(&admin, &base_coin).await;
+
+    let result = env
+        .send(
+            &[initialize(&intruder.pubkey(), &mint, 1_000)],
+            &[&intruder],
+        )
+        .await;
+    assert_error(result, LoyaltyError::IncorrectUpdateAuthority);
+
+    let result = env
+        .send(
+            &[initialize(&admin.pubkey(), &unhooked_mint, 1_000)],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, LoyaltyError::IncorrectTransferHook);
+
+    let result = env
+        .send(&[initialize(&admin.pubkey(), &mint, 10_001)], &[&admin])
+        .await;
+    assert_error(result, LoyaltyError::PointsBasisPtsCannotExceed10000);
+}
+
+#[tokio::test]
+async fn execute_rejects_calls_outside_transfers() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let (mint, base_coin) = create_loyalty_mint(&mut env, &admin).await;
+    env.send(
+        &[
+            initialize(&admin.pubkey(), &mint, 1_000),
+            register_merchant(&admin.pubkey(), &mint, admin.pubkey()),
+            open_points(&admin.pubkey(), &mint, admin.pubkey()),
+        ],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000_000)
+        .await;
+    env.issue(&admin, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+
+    // a self-transfer to the merchant, with every account the hook asks for
+    let source = mint_token_account(&admin.pubkey(), &mint);
+    let mut ix = spl_transfer_hook_interface::instruction::execute(
+        &loyalty_hook::ID,
+        &source,
+        &mint,
+        &source,
+        &admin.pubkey(),
+        &Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], &loyalty_hook::ID)
+            .0,
+        1_000_000,
+    );
+    ix.accounts.extend(
+        env.transfer_hook_accounts(
+            &mint,
+            vec![candy_wrapper_client::instructions::TokenTransfer {
+                source,
+                destination: source,
+                authority: admin.pubkey(),
+            }],
+            1_000_000,
+        )
+        .await
+        .into_iter()
+        .filter(|meta| meta.pubkey != loyalty_hook::ID),
+    );
+    let result = env.send(&[ix], &[]).await;
+    assert_error(result, LoyaltyError::NotTransferring);
+}
@@ -0,0 +1,21 @@
+//! In-process tests of every candy wrapper instruction, run against the Token and Token-2022
+//! programs bundled with solana-program-test. The candy wrapper program runs natively unless
+//! `SBF_OUT_DIR` points at its compiled shared object; the loyalty hook always runs natively.
+//!
+//! Every error variant is triggered except `BaseCoinIsNotZero`, which no instruction returns,
+//! and `DivisionByZero` and `EmptySupply`, which the supply and amount checks preceding the
+//! pricing math rule out, and `Undercollateralized`, which the rounding of the pricing math
+//! rules out. The math crate's own tests cover those three.
+
+mod admin;
+mod attestation;
+mod compliance;
+mod fees;
+mod fuzz;
+mod gift_card;
+mod issuance;
+mod loyalty;
+mod non_transferable;
+mod protocol;
+mod setup;
+mod transfer_hook;
@@ -0,0 +1,122 @@
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        error::TokenError,
+        extension::{
+            non_transferable::NonTransferable, transfer_fee::TransferFeeConfig,
+            BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::Mint,
+    },
+};
+use candy_wrapper::error::CustomError;
+use candy_wrapper_client::{instructions, FeePayout, TransferFeeArgs};
+
+use crate::setup::*;
+
+#[tokio::test]
+async fn non_transferable_tokens_are_issued_and_redeemed_but_not_transferred() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let alice = env.create_user().await;
+    let bob = Pubkey::new_unique();
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.non_transferable = true;
+    args.transfer_fee_args = TransferFeeArgs {
+        fee_basis_pts: 0,
+        max_fee: 0,
+    };
+    let mint = env.try_create_mint(&admin, &base_coin, args).await.unwrap();
+
+    let account = env.account(&mint).await.unwrap();
+    let state = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    assert!(state.get_extension::<NonTransferable>().is_ok());
+    assert!(state.get_extension::<TransferFeeConfig>().is_err());
+
+    env.fund_base_coin(&base_coin, &alice.pubkey(), 1_000_000)
+        .await;
+    env.issue(&alice, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    let result = env.try_transfer_mint(&alice, &bob, &mint, 1_000_000).await;
+    assert_error(result, TokenError::NonTransferable as u32);
+
+    // issuance fees are still withdrawn, there are just no transfer fees to go with them
+    let fee_vault_before = env.fee_vault(&mint).await;
+    assert!(fee_vault_before > 0);
+    env.create_token_account(&admin.pubkey(), &base_coin.mint, &base_coin.token_program)
+        .await;
+    env.withdraw_fees(&mint, &base_coin, &[]).await.unwrap();
+    let protocol_fees = env.authority(&mint).await.protocol_fees_accrued;
+    assert_eq!(env.fee_vault(&mint).await, protocol_fees);
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&admin.pubkey()))
+            .await,
+        fee_vault_before - protocol_fees
+    );
+    env.send(
+        &[instructions::attest_reserves(
+            &admin.pubkey(),
+            &mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    let balance = env
+        .token_balance(&mint_token_account(&alice.pubkey(), &mint))
+        .await;
+    env.redeem(&alice, &mint, &base_coin, balance)
+        .await
+        .unwrap();
+    assert_eq!(
+        env.token_balance(&mint_token_account(&alice.pubkey(), &mint))
+            .await,
+        0
+    );
+
+    // fees paid out in the token could never reach the fee collector
+    let result = env
+        .send(
+            &[instructions::change_fee_payout(
+                &admin.pubkey(),
+                &mint,
+                FeePayout::Mint,
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::NonTransferableCannotPayFeesInMint);
+
+    // there is no transfer fee to change
+    let result = env
+        .send(
+            &[instructions::change_transfer_fee(
+                &admin.pubkey(),
+                &mint,
+                100,
+                1_000,
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, CustomError::MintIsNonTransferable);
+}
+
+#[tokio::test]
+async fn non_transferable_mint_rejects_transfer_fee_args() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mut args = default_args(&admin.pubkey(), &base_coin);
+    args.non_transferable = true;
+    let result = env.try_create_mint(&admin, &base_coin, args).await;
+    assert_error(
+        result.map(|_| ()),
+        CustomError::NonTransferableCannotChargeTransferFees,
+    );
+}
@@ -0,0 +1,121 @@
+use anchor_spl::{token::spl_token, token_2022::spl_token_2022};
+use candy_wrapper::error::CustomError;
+use candy_wrapper_client::{
+    instructions::{self, ProtocolFeeClaim},
+    pda, PROTOCOL_WALLET,
+};
+
+use crate::setup::*;
+
+fn claim(mint: &Pubkey, base_coin: &BaseCoin) -> ProtocolFeeClaim {
+    ProtocolFeeClaim {
+        mint: *mint,
+        base_coin: base_coin.mint,
+        token_program_base_coin: base_coin.token_program,
+    }
+}
+
+// a mint whose issuance fee of 10_000 was withdrawn, leaving 1_000 for the protocol
+async fn mint_with_protocol_fees(env: &mut Env, base_coin: &BaseCoin) -> Pubkey {
+    let admin = env.create_user().await;
+    let mint = env.create_mint(&admin, base_coin).await;
+    env.fund_base_coin(base_coin, &admin.pubkey(), 1_000_000)
+        .await;
+    env.issue(&admin, &mint, base_coin, 1_000_000)
+        .await
+        .unwrap();
+    env.withdraw_fees(&mint, base_coin, &[]).await.unwrap();
+    assert_eq!(env.authority(&mint).await.protocol_fees_accrued, 1_000);
+    mint
+}
+
+#[tokio::test]
+async fn claim_protocol_fees_of_several_mints() {
+    let mut env = Env::new().await;
+    let token = env.create_base_coin(spl_token::ID).await;
+    let token_2022 = env.create_base_coin(spl_token_2022::ID).await;
+    let first_mint = mint_with_protocol_fees(&mut env, &token).await;
+    let second_mint = mint_with_protocol_fees(&mut env, &token_2022).await;
+    // nothing accrued yet, so it is skipped
+    let admin = env.create_user().await;
+    let third_mint = env.create_mint(&admin, &token).await;
+
+    // claiming is permissionless, the fees always go to the protocol wallet
+    let claimer = env.create_user().await;
+    env.send(
+        &[instructions::claim_protocol_fees(
+            &claimer.pubkey(),
+            &[
+                claim(&first_mint, &token),
+                claim(&second_mint, &token_2022),
+                claim(&third_mint, &token),
+            ],
+        )],
+        &[&claimer],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        env.token_balance(&token.token_account(&PROTOCOL_WALLET))
+            .await,
+        1_000
+    );
+    assert_eq!(
+        env.token_balance(&token_2022.token_account(&PROTOCOL_WALLET))
+            .await,
+        1_000
+    );
+    for mint in [first_mint, second_mint] {
+        assert_eq!(env.fee_vault(&mint).await, 0);
+        assert_eq!(env.authority(&mint).await.protocol_fees_accrued, 0);
+    }
+}
+
+#[tokio::test]
+async fn claim_protocol_fees_rejects_incorrect_accounts() {
+    let mut env = Env::new().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint = mint_with_protocol_fees(&mut env, &base_coin).await;
+    let other_mint = mint_with_protocol_fees(&mut env, &base_coin).await;
+    let payer = env.payer();
+
+    // accounts come in groups of five per mint
+    let mut ix = instructions::claim_protocol_fees(&payer.pubkey(), &[claim(&mint, &base_coin)]);
+    ix.accounts.pop();
+    let result = env.send(&[ix], &[]).await;
+    assert_error(result, CustomError::IncorrectProtocolFeeClaimAccounts);
+
+    // the fee vault of another mint
+    let ix = instructions::claim_protocol_fees(&payer.pubkey(), &[claim(&mint, &base_coin)]);
+    let result = env
+        .send(&[with_account(ix, 2, pda::fee_vault(&other_mint).0)], &[])
+        .await;
+    assert_error(result, CustomError::IncorrectProtocolFeeClaimAccounts);
+
+    // a token account not owned by the protocol wallet
+    env.create_token_account(&payer.pubkey(), &base_coin.mint, &base_coin.token_program)
+        .await;
+    let ix = instructions::claim_protocol_fees(&payer.pubkey(), &[claim(&mint, &base_coin)]);
+    let result = env
+        .send(
+            &[with_account(
+                ix,
+                4,
+                base_coin.token_account(&payer.pubkey()),
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, CustomError::IncorrectProtocolFeeClaimAccounts);
+}
+
+#[tokio::test]
+async fn set_protocol_fee_rejects_other_signers() {
+    let mut env = Env::new().await;
+    let intruder = env.create_user().await;
+
+    let ix = with_account(instructions::set_protocol_fee(0), 0, intruder.pubkey());
+    let result = env.send(&[ix], &[&intruder]).await;
+    assert_error(result, CustomError::IncorrectUpdateAuthority);
+}
@@ -0,0 +1,861 @@
+use std::collections::HashSet;
+
+use anchor_lang::{
+    prelude::{AccountInfo, AccountMeta, ProgramError},
+    solana_program::entrypoint::ProgramResult,
+    AccountSerialize,
+};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{
+                instruction::{initialize_transfer_fee_config, transfer_checked_with_fee},
+                TransferFeeConfig,
+            },
+            transfer_hook, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        },
+        offchain::add_extra_account_metas,
+        state::{Account as TokenAccount, Mint},
+    },
+};
+use candy_wrapper::state::PROTOCOL_FEE_CONFIG_SPACE;
+use candy_wrapper_client::{
+    instructions::{self, TokenTransfer},
+    pda,
+    state::{decode_authority, decode_reserve_attestation, fee_payout},
+    Authority, CreateMintArgs, FeePayout, ProtocolFeeConfig, ReserveAttestation, TransferFeeArgs,
+};
+use solana_program_test::{
+    processor, BanksClientError, BanksTransactionResultWithMetadata, ProgramTest,
+    ProgramTestContext,
+};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    signature::Signature,
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_tlv_account_resolution::{
+    account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
+};
+use spl_transfer_hook_interface::{
+    get_extra_account_metas_address,
+    instruction::{ExecuteInstruction, TransferHookInstruction},
+};
+
+pub use anchor_lang::prelude::Pubkey;
+pub use solana_sdk::{signature::Keypair, signer::Signer};
+
+pub const DECIMALS: u8 = 6;
+pub const MINT_TO_BASE_RATIO: u16 = 100;
+pub const ISSUANCE_FEE_BASIS_PTS: u16 = 100;
+pub const REDEMPTION_FEE_BASIS_PTS: u16 = 100;
+pub const TRANSFER_FEE_BASIS_PTS: u16 = 50;
+pub const PROTOCOL_FEE_BASIS_PTS: u16 = 1_000;
+
+/// Transfer hook counting the transfers of the mints seeded by [`Env::seed_transfer_hook`].
+pub const TRANSFER_HOOK_ID: Pubkey = Pubkey::new_from_array([7; 32]);
+
+// anchor's entry ties the account slice to the lifetime of the account infos it holds
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    candy_wrapper::entry(program_id, accounts, data)
+}
+
+fn process_loyalty_hook(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    loyalty_hook::entry(program_id, accounts, data)
+}
+
+// counts the transfers of a mint in a counter account derived from it, which it requires as
+// its only extra account
+fn process_transfer_hook(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let TransferHookInstruction::Execute { .. } = TransferHookInstruction::unpack(data)? else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    ExtraAccountMetaList::check_account_infos::<ExecuteInstruction>(
+        accounts,
+        data,
+        program_id,
+        &accounts[4].try_borrow_data()?,
+    )?;
+    let mut counter = accounts[5].try_borrow_mut_data()?;
+    let count = u64::from_le_bytes(counter[..8].try_into().unwrap()) + 1;
+    counter[..8].copy_from_slice(&count.to_le_bytes());
+    Ok(())
+}
+
+fn transfer_hook_counter(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"counter", mint.as_ref()], &TRANSFER_HOOK_ID).0
+}
+
+/// A base coin mint and the token program owning it.
+#[derive(Clone, Copy)]
+pub struct BaseCoin {
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl BaseCoin {
+    pub fn token_account(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.mint, &self.token_program)
+    }
+}
+
+pub fn mint_token_account(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, &spl_token_2022::ID)
+}
+
+pub fn default_args(admin: &Pubkey, base_coin: &BaseCoin) -> CreateMintArgs {
+    CreateMintArgs {
+        admin: *admin,
+        base_coin: base_coin.mint,
+        mint_to_base_ratio: MINT_TO_BASE_RATIO,
+        issuance_fee_basis_pts: ISSUANCE_FEE_BASIS_PTS,
+        redemption_fee_basis_pts: REDEMPTION_FEE_BASIS_PTS,
+        fee_collector: *admin,
+        transfer_fee_args: TransferFeeArgs {
+            fee_basis_pts: TRANSFER_FEE_BASIS_PTS,
+            max_fee: u64::MAX,
+        },
+        transfer_hook_args: None,
+        gift_card: false,
+        blocklist: false,
+        freezable: false,
+        default_frozen: false,
+        non_transferable: false,
+        mint_seed: None,
+    }
+}
+
+/// Replaces the account at `index` of `ix`, keeping its signer and writable flags.
+pub fn with_account(mut ix: Instruction, index: usize, key: Pubkey) -> Instruction {
+    ix.accounts[index].pubkey = key;
+    ix
+}
+
+/// Asserts that a transaction failed with `error` from the candy wrapper or loyalty hook program.
+pub fn assert_error(result: Result<(), BanksClientError>, error: impl Into<u32>) {
+    let expected = error.into();
+    match result.map_err(|err| err.unwrap()) {
+        Err(TransactionError::InstructionError(_, InstructionError::Custom(code)))
+            if code == expected => {}
+        other => panic!("expected custom error {expected}, got {other:?}"),
+    }
+}
+
+pub struct Env {
+    pub ctx: ProgramTestContext,
+    sent: HashSet<Signature>,
+}
+
+impl Env {
+    pub async fn new() -> Self {
+        let mut program_test = ProgramTest::new(
+            "candy_wrapper",
+            candy_wrapper::ID,
+            processor!(process_instruction),
+        );
+
+        // set_protocol_fee can only be signed by the protocol wallet, so the config is seeded
+        let (config, bump) = pda::config();
+        let mut data = Vec::with_capacity(PROTOCOL_FEE_CONFIG_SPACE);
+        ProtocolFeeConfig {
+            bump,
+            fee_basis_pts: PROTOCOL_FEE_BASIS_PTS,
+        }
+        .try_serialize(&mut data)
+        .unwrap();
+        data.resize(PROTOCOL_FEE_CONFIG_SPACE, 0);
+        program_test.add_account(
+            config,
+            Account {
+                lamports: 1_000_000_000,
+                data,
+                owner: candy_wrapper::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        program_test.add_program(
+            "loyalty_hook",
+            loyalty_hook::ID,
+            processor!(process_loyalty_hook),
+        );
+        program_test.add_program(
+            "transfer_hook",
+            TRANSFER_HOOK_ID,
+            processor!(process_transfer_hook),
+        );
+
+        Self {
+            ctx: program_test.start_with_context().await,
+            sent: HashSet::new(),
+        }
+    }
+
+    pub fn payer(&self) -> Keypair {
+        self.ctx.payer.insecure_clone()
+    }
+
+    pub async fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        self.send_with_metadata(instructions, signers)
+            .await?
+            .result
+            .map_err(BanksClientError::TransactionError)
+    }
+
+    /// Sends the instructions and returns the compute units the transaction consumed.
+    pub async fn compute_units(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> u64 {
+        let result = self
+            .send_with_metadata(instructions, signers)
+            .await
+            .unwrap();
+        result.result.unwrap();
+        result.metadata.unwrap().compute_units_consumed
+    }
+
+    async fn send_with_metadata(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<BanksTransactionResultWithMetadata, BanksClientError> {
+        let payer = self.payer();
+        let mut all_signers = vec![&payer];
+        all_signers.extend_from_slice(signers);
+        let mut blockhash = self.ctx.banks_client.get_latest_blockhash().await?;
+        loop {
+            let transaction = Transaction::new_signed_with_payer(
+                instructions,
+                Some(&payer.pubkey()),
+                &all_signers,
+                blockhash,
+            );
+            // the same transaction was already sent with this blockhash, and would either be
+            // rejected or answered with its earlier result, so wait for a new one
+            if !self.sent.insert(transaction.signatures[0]) {
+                blockhash = self.ctx.get_new_latest_blockhash().await?;
+                continue;
+            }
+            let result = self
+                .ctx
+                .banks_client
+                .process_transaction_with_metadata(transaction)
+                .await?;
+            if result.result == Err(TransactionError::AlreadyProcessed) {
+                blockhash = self.ctx.get_new_latest_blockhash().await?;
+                continue;
+            }
+            return Ok(result);
+        }
+    }
+
+    pub async fn create_user(&mut self) -> Keypair {
+        let user = Keypair::new();
+        let payer = self.payer();
+        self.send(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &user.pubkey(),
+                10_000_000_000,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        user
+    }
+
+    pub async fn create_base_coin(&mut self, token_program: Pubkey) -> BaseCoin {
+        let mint = Keypair::new();
+        let payer = self.payer();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let initialize_mint = if token_program == spl_token::ID {
+            spl_token::instruction::initialize_mint2(
+                &token_program,
+                &mint.pubkey(),
+                &payer.pubkey(),
+                None,
+                DECIMALS,
+            )
+        } else {
+            spl_token_2022::instruction::initialize_mint2(
+                &token_program,
+                &mint.pubkey(),
+                &payer.pubkey(),
+                None,
+                DECIMALS,
+            )
+        }
+        .unwrap();
+        self.send(
+            &[
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &mint.pubkey(),
+                    rent.minimum_balance(Mint::LEN),
+                    Mint::LEN as u64,
+                    &token_program,
+                ),
+                initialize_mint,
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+        BaseCoin {
+            mint: mint.pubkey(),
+            token_program,
+        }
+    }
+
+    /// A Token-2022 base coin whose transfers withhold `fee_basis_pts`, at most `max_fee`.
+    pub async fn create_base_coin_with_transfer_fee(
+        &mut self,
+        fee_basis_pts: u16,
+        max_fee: u64,
+    ) -> BaseCoin {
+        let mint = Keypair::new();
+        let payer = self.payer();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let mint_len =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig])
+                .unwrap();
+        self.send(
+            &[
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &mint.pubkey(),
+                    rent.minimum_balance(mint_len),
+                    mint_len as u64,
+                    &spl_token_2022::ID,
+                ),
+                initialize_transfer_fee_config(
+                    &spl_token_2022::ID,
+                    &mint.pubkey(),
+                    None,
+                    None,
+                    fee_basis_pts,
+                    max_fee,
+                )
+                .unwrap(),
+                spl_token_2022::instruction::initialize_mint2(
+                    &spl_token_2022::ID,
+                    &mint.pubkey(),
+                    &payer.pubkey(),
+                    None,
+                    DECIMALS,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+        BaseCoin {
+            mint: mint.pubkey(),
+            token_program: spl_token_2022::ID,
+        }
+    }
+
+    /// A Token-2022 base coin whose transfers invoke [`TRANSFER_HOOK_ID`], which requires an
+    /// extra account resolved from its `ExtraAccountMetaList`.
+    pub async fn create_base_coin_with_transfer_hook(&mut self) -> BaseCoin {
+        let mint = Keypair::new();
+        let payer = self.payer();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let mint_len =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferHook])
+                .unwrap();
+        self.send(
+            &[
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &mint.pubkey(),
+                    rent.minimum_balance(mint_len),
+                    mint_len as u64,
+                    &spl_token_2022::ID,
+                ),
+                transfer_hook::instruction::initialize(
+                    &spl_token_2022::ID,
+                    &mint.pubkey(),
+                    None,
+                    Some(TRANSFER_HOOK_ID),
+                )
+                .unwrap(),
+                spl_token_2022::instruction::initialize_mint2(
+                    &spl_token_2022::ID,
+                    &mint.pubkey(),
+                    &payer.pubkey(),
+                    None,
+                    DECIMALS,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+
+        self.seed_transfer_hook(&mint.pubkey()).await;
+        BaseCoin {
+            mint: mint.pubkey(),
+            token_program: spl_token_2022::ID,
+        }
+    }
+
+    /// Seeds the `ExtraAccountMetaList` and counter of [`TRANSFER_HOOK_ID`] for `mint`, rather
+    /// than creating them with an instruction of the hook.
+    pub async fn seed_transfer_hook(&mut self, mint: &Pubkey) {
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let extra_account_metas = [ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"counter".to_vec(),
+                },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            true,
+        )
+        .unwrap()];
+        let mut data = vec![0; ExtraAccountMetaList::size_of(extra_account_metas.len()).unwrap()];
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas).unwrap();
+        for (address, data) in [
+            (
+                get_extra_account_metas_address(mint, &TRANSFER_HOOK_ID),
+                data,
+            ),
+            (transfer_hook_counter(mint), vec![0; 8]),
+        ] {
+            let account = Account {
+                lamports: rent.minimum_balance(data.len()),
+                data,
+                owner: TRANSFER_HOOK_ID,
+                executable: false,
+                rent_epoch: 0,
+            };
+            self.ctx.set_account(&address, &account.into());
+        }
+    }
+
+    /// Accounts the transfer hook of `mint`, if any, needs for `transfers`.
+    pub async fn transfer_hook_accounts(
+        &mut self,
+        mint: &Pubkey,
+        transfers: Vec<TokenTransfer>,
+        amount: u64,
+    ) -> Vec<AccountMeta> {
+        let banks_client = self.ctx.banks_client.clone();
+        let fetch = |address: Pubkey| {
+            let mut banks_client = banks_client.clone();
+            async move {
+                let account = banks_client.get_account(address).await?;
+                Ok(account.map(|account| account.data))
+            }
+        };
+
+        let mut accounts: Vec<AccountMeta> = vec![];
+        for transfer in transfers {
+            let mut ix = spl_token_2022::instruction::transfer_checked(
+                &spl_token_2022::ID,
+                &transfer.source,
+                mint,
+                &transfer.destination,
+                &transfer.authority,
+                &[],
+                amount,
+                DECIMALS,
+            )
+            .unwrap();
+            add_extra_account_metas(
+                &mut ix,
+                &transfer.source,
+                mint,
+                &transfer.destination,
+                &transfer.authority,
+                amount,
+                fetch,
+            )
+            .await
+            .unwrap();
+            for meta in ix.accounts.into_iter().skip(4) {
+                match accounts
+                    .iter_mut()
+                    .find(|account| account.pubkey == meta.pubkey)
+                {
+                    Some(account) => account.is_writable |= meta.is_writable,
+                    None => accounts.push(meta),
+                }
+            }
+        }
+        accounts
+    }
+
+    /// Transfers of `mint` counted by its transfer hook.
+    pub async fn transfer_hook_invocations(&mut self, mint: &Pubkey) -> u64 {
+        let account = self.account(&transfer_hook_counter(mint)).await.unwrap();
+        u64::from_le_bytes(account.data[..8].try_into().unwrap())
+    }
+
+    pub async fn create_token_account(
+        &mut self,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+    ) {
+        let payer = self.payer();
+        self.send(
+            &[create_associated_token_account_idempotent(
+                &payer.pubkey(),
+                owner,
+                mint,
+                token_program,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// A token account of `owner` for `mint` at a fresh address, not its associated one.
+    pub async fn create_other_token_account(
+        &mut self,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+    ) -> Pubkey {
+        let payer = self.payer();
+        let token_account = Keypair::new();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        // Token-2022 mints may require extensions on their token accounts
+        let mint_account = self.account(mint).await.unwrap();
+        let extensions = StateWithExtensions::<Mint>::unpack(&mint_account.data)
+            .unwrap()
+            .get_extension_types()
+            .unwrap();
+        let len = ExtensionType::try_calculate_account_len::<TokenAccount>(
+            &ExtensionType::get_required_init_account_extensions(&extensions),
+        )
+        .unwrap();
+        self.send(
+            &[
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &token_account.pubkey(),
+                    rent.minimum_balance(len),
+                    len as u64,
+                    token_program,
+                ),
+                spl_token_2022::instruction::initialize_account3(
+                    token_program,
+                    &token_account.pubkey(),
+                    mint,
+                    owner,
+                )
+                .unwrap(),
+            ],
+            &[&token_account],
+        )
+        .await
+        .unwrap();
+        token_account.pubkey()
+    }
+
+    pub async fn fund_base_coin(&mut self, base_coin: &BaseCoin, owner: &Pubkey, amount: u64) {
+        self.create_token_account(owner, &base_coin.mint, &base_coin.token_program)
+            .await;
+        let payer = self.payer();
+        let destination = base_coin.token_account(owner);
+        let mint_to = if base_coin.token_program == spl_token::ID {
+            spl_token::instruction::mint_to(
+                &base_coin.token_program,
+                &base_coin.mint,
+                &destination,
+                &payer.pubkey(),
+                &[],
+                amount,
+            )
+        } else {
+            spl_token_2022::instruction::mint_to(
+                &base_coin.token_program,
+                &base_coin.mint,
+                &destination,
+                &payer.pubkey(),
+                &[],
+                amount,
+            )
+        }
+        .unwrap();
+        self.send(&[mint_to], &[]).await.unwrap();
+    }
+
+    pub async fn try_create_mint(
+        &mut self,
+        admin: &Keypair,
+        base_coin: &BaseCoin,
+        args: CreateMintArgs,
+    ) -> Result<Pubkey, BanksClientError> {
+        let mint = Keypair::new();
+        let ix = instructions::create_mint(
+            &admin.pubkey(),
+            &mint.pubkey(),
+            &base_coin.token_program,
+            args,
+        );
+        self.send(&[ix], &[admin, &mint]).await?;
+        Ok(mint.pubkey())
+    }
+
+    pub async fn create_mint(&mut self, admin: &Keypair, base_coin: &BaseCoin) -> Pubkey {
+        let args = default_args(&admin.pubkey(), base_coin);
+        self.try_create_mint(admin, base_coin, args).await.unwrap()
+    }
+
+    pub async fn issue(
+        &mut self,
+        user: &Keypair,
+        mint: &Pubkey,
+        base_coin: &BaseCoin,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let mut ix = instructions::issue_mint(
+            &user.pubkey(),
+            mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+            amount,
+        );
+        let transfers = instructions::issue_mint_transfers(
+            &user.pubkey(),
+            mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+        );
+        ix.accounts.extend(
+            self.transfer_hook_accounts(&base_coin.mint, transfers, amount)
+                .await,
+        );
+        self.send(&[ix], &[user]).await
+    }
+
+    pub async fn redeem(
+        &mut self,
+        user: &Keypair,
+        mint: &Pubkey,
+        base_coin: &BaseCoin,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let mut ix = instructions::redeem_basecoin(
+            &user.pubkey(),
+            mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+            amount,
+        );
+        let transfers = instructions::redeem_basecoin_transfers(
+            &user.pubkey(),
+            mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+        );
+        ix.accounts.extend(
+            self.transfer_hook_accounts(&base_coin.mint, transfers, 0)
+                .await,
+        );
+        self.send(&[ix], &[user]).await
+    }
+
+    /// Transfers creator tokens between the owners' token accounts, withholding the mint's
+    /// current transfer fee in the destination account.
+    pub async fn transfer_mint(&mut self, from: &Keypair, to: &Pubkey, mint: &Pubkey, amount: u64) {
+        self.try_transfer_mint(from, to, mint, amount)
+            .await
+            .unwrap();
+    }
+
+    pub async fn try_transfer_mint(
+        &mut self,
+        from: &Keypair,
+        to: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        self.create_token_account(to, mint, &spl_token_2022::ID)
+            .await;
+        let epoch = self
+            .ctx
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .epoch;
+        let account = self.account(mint).await.unwrap();
+        // non-transferable mints charge no transfer fee
+        let fee = StateWithExtensions::<Mint>::unpack(&account.data)
+            .unwrap()
+            .get_extension::<TransferFeeConfig>()
+            .map_or(0, |config| {
+                config.calculate_epoch_fee(epoch, amount).unwrap()
+            });
+        let transfer = TokenTransfer {
+            source: mint_token_account(&from.pubkey(), mint),
+            destination: mint_token_account(to, mint),
+            authority: from.pubkey(),
+        };
+        let mut ix = transfer_checked_with_fee(
+            &spl_token_2022::ID,
+            &transfer.source,
+            mint,
+            &transfer.destination,
+            &transfer.authority,
+            &[],
+            amount,
+            DECIMALS,
+            fee,
+        )
+        .unwrap();
+        ix.accounts.extend(
+            self.transfer_hook_accounts(mint, vec![transfer], amount)
+                .await,
+        );
+        self.send(&[ix], &[from]).await
+    }
+
+    /// Withdraws the mint's fees to its current fee collector in its chosen payout, harvesting
+    /// the fees withheld in `holders` first when there are any.
+    pub async fn withdraw_fees(
+        &mut self,
+        mint: &Pubkey,
+        base_coin: &BaseCoin,
+        holders: &[Pubkey],
+    ) -> Result<(), BanksClientError> {
+        let authority = self.authority(mint).await;
+        let payer = self.payer();
+        let mut ix = if holders.is_empty() {
+            instructions::withdraw_fees(
+                &payer.pubkey(),
+                mint,
+                &base_coin.mint,
+                &base_coin.token_program,
+                &authority.fee_collector,
+                fee_payout(&authority),
+            )
+        } else {
+            instructions::harvest_and_withdraw_fees(
+                &payer.pubkey(),
+                mint,
+                &base_coin.mint,
+                &base_coin.token_program,
+                &authority.fee_collector,
+                fee_payout(&authority),
+                holders,
+            )
+        };
+        let transfers = instructions::withdraw_fees_transfers(
+            mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+            &authority.fee_collector,
+        );
+        ix.accounts.extend(
+            self.transfer_hook_accounts(&base_coin.mint, transfers, 0)
+                .await,
+        );
+        if fee_payout(&authority) == FeePayout::Mint {
+            let transfers =
+                instructions::withdraw_fees_mint_transfers(mint, &authority.fee_collector);
+            ix.accounts
+                .extend(self.transfer_hook_accounts(mint, transfers, 0).await);
+        }
+        self.send(&[ix], &[]).await
+    }
+
+    pub async fn account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.ctx.banks_client.get_account(*address).await.unwrap()
+    }
+
+    pub async fn token_balance(&mut self, address: &Pubkey) -> u64 {
+        match self.account(address).await {
+            Some(account) => {
+                StateWithExtensions::<TokenAccount>::unpack(&account.data)
+                    .unwrap()
+                    .base
+                    .amount
+            }
+            None => 0,
+        }
+    }
+
+    pub async fn withheld_in_account(&mut self, address: &Pubkey) -> u64 {
+        let account = self.account(address).await.unwrap();
+        let state = StateWithExtensions::<TokenAccount>::unpack(&account.data).unwrap();
+        state
+            .get_extension::<spl_token_2022::extension::transfer_fee::TransferFeeAmount>()
+            .unwrap()
+            .withheld_amount
+            .into()
+    }
+
+    pub async fn supply(&mut self, mint: &Pubkey) -> u64 {
+        let account = self.account(mint).await.unwrap();
+        StateWithExtensions::<Mint>::unpack(&account.data)
+            .unwrap()
+            .base
+            .supply
+    }
+
+    pub async fn authority(&mut self, mint: &Pubkey) -> Authority {
+        let account = self.account(&pda::authority(mint).0).await.unwrap();
+        decode_authority(&account.data).unwrap()
+    }
+
+    pub async fn reserves(&mut self, mint: &Pubkey, base_coin: &BaseCoin) -> u64 {
+        self.token_balance(&pda::reserves(
+            mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+        ))
+        .await
+    }
+
+    pub async fn fee_vault(&mut self, mint: &Pubkey) -> u64 {
+        self.token_balance(&pda::fee_vault(mint).0).await
+    }
+
+    pub async fn reserve_attestation(&mut self, mint: &Pubkey) -> ReserveAttestation {
+        let account = self
+            .account(&pda::reserve_attestation(mint).0)
+            .await
+            .unwrap();
+        decode_reserve_attestation(&account.data).unwrap()
+    }
+}
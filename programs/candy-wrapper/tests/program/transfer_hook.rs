@@ -0,0 +1,315 @@
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        extension::{transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions},
+        state::Mint,
+    },
+};
+use candy_wrapper::error::CustomError;
+use candy_wrapper_client::{
+    instructions::{self, ProtocolFeeClaim},
+    pda, FeePayout, FeeSplitRecipient, TransferHookArgs, PROTOCOL_WALLET,
+};
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+use spl_transfer_hook_interface::error::TransferHookError;
+
+use crate::setup::*;
+
+#[tokio::test]
+async fn base_coin_transfers_invoke_transfer_hook() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let holder = Pubkey::new_unique();
+    let base_coin = env.create_base_coin_with_transfer_hook().await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000_000)
+        .await;
+
+    // the issuance fee and the deposit
+    env.issue(&admin, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    assert_eq!(env.transfer_hook_invocations(&base_coin.mint).await, 2);
+    assert_eq!(env.reserves(&mint, &base_coin).await, 990_000);
+
+    // the redemption fee and the payout
+    env.redeem(&admin, &mint, &base_coin, 49_500_000)
+        .await
+        .unwrap();
+    assert_eq!(env.transfer_hook_invocations(&base_coin.mint).await, 4);
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&admin.pubkey()))
+            .await,
+        490_050
+    );
+
+    // the harvested fees are converted out of the reserves and the fee vault is swept, with the
+    // hook's accounts told apart from the holder token accounts to harvest
+    env.transfer_mint(&admin, &holder, &mint, 10_000_000).await;
+    let holder_account = mint_token_account(&holder, &mint);
+    env.withdraw_fees(&mint, &base_coin, &[holder_account])
+        .await
+        .unwrap();
+    assert_eq!(env.withheld_in_account(&holder_account).await, 0);
+    assert_eq!(env.transfer_hook_invocations(&base_coin.mint).await, 7);
+    assert_eq!(
+        env.fee_vault(&mint).await,
+        env.authority(&mint).await.protocol_fees_accrued
+    );
+}
+
+#[tokio::test]
+async fn protocol_fee_claims_invoke_base_coin_transfer_hook() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin_with_transfer_hook().await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000_000)
+        .await;
+    env.issue(&admin, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    env.withdraw_fees(&mint, &base_coin, &[]).await.unwrap();
+    env.create_token_account(&PROTOCOL_WALLET, &base_coin.mint, &base_coin.token_program)
+        .await;
+    let invocations = env.transfer_hook_invocations(&base_coin.mint).await;
+
+    let claim = ProtocolFeeClaim {
+        mint,
+        base_coin: base_coin.mint,
+        token_program_base_coin: base_coin.token_program,
+    };
+    let mut ix = instructions::claim_protocol_fees(&admin.pubkey(), std::slice::from_ref(&claim));
+    ix.accounts.extend(
+        env.transfer_hook_accounts(
+            &base_coin.mint,
+            instructions::claim_protocol_fees_transfers(&claim),
+            0,
+        )
+        .await,
+    );
+    env.send(&[ix], &[&admin]).await.unwrap();
+
+    assert_eq!(
+        env.transfer_hook_invocations(&base_coin.mint).await,
+        invocations + 1
+    );
+    assert_eq!(
+        env.token_balance(&base_coin.token_account(&PROTOCOL_WALLET))
+            .await,
+        1_000
+    );
+    assert_eq!(env.fee_vault(&mint).await, 0);
+}
+
+#[tokio::test]
+async fn fee_split_distributions_invoke_base_coin_transfer_hook() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let recipients = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let base_coin = env.create_base_coin_with_transfer_hook().await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000_000)
+        .await;
+    env.send(
+        &[instructions::set_fee_split(
+            &admin.pubkey(),
+            &mint,
+            &base_coin.mint,
+            &base_coin.token_program,
+            recipients
+                .iter()
+                .map(|&wallet| FeeSplitRecipient {
+                    wallet,
+                    basis_pts: 5_000,
+                })
+                .collect(),
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    env.issue(&admin, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    env.withdraw_fees(&mint, &base_coin, &[]).await.unwrap();
+    for recipient in &recipients {
+        env.create_token_account(recipient, &base_coin.mint, &base_coin.token_program)
+            .await;
+    }
+    let invocations = env.transfer_hook_invocations(&base_coin.mint).await;
+
+    let mut ix = instructions::distribute(
+        &mint,
+        &base_coin.mint,
+        &base_coin.token_program,
+        &recipients,
+    );
+    ix.accounts.extend(
+        env.transfer_hook_accounts(
+            &base_coin.mint,
+            instructions::distribute_transfers(
+                &mint,
+                &base_coin.mint,
+                &base_coin.token_program,
+                &recipients,
+            ),
+            0,
+        )
+        .await,
+    );
+    env.send(&[ix], &[]).await.unwrap();
+
+    assert_eq!(
+        env.transfer_hook_invocations(&base_coin.mint).await,
+        invocations + 2
+    );
+    for recipient in &recipients {
+        assert_eq!(
+            env.token_balance(&base_coin.token_account(recipient)).await,
+            4_500
+        );
+    }
+}
+
+#[tokio::test]
+async fn issue_rejects_missing_transfer_hook_accounts() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin_with_transfer_hook().await;
+    let mint = env.create_mint(&admin, &base_coin).await;
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000_000)
+        .await;
+
+    let ix = instructions::issue_mint(
+        &admin.pubkey(),
+        &mint,
+        &base_coin.mint,
+        &base_coin.token_program,
+        1_000_000,
+    );
+    let result = env.send(&[ix], &[&admin]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TransferHookError::IncorrectAccount as u32)
+        )
+    );
+}
+
+async fn transfer_hook_program(env: &mut Env, mint: &Pubkey) -> Option<Pubkey> {
+    let account = env.account(mint).await.unwrap();
+    let state = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    state
+        .get_extension::<TransferHook>()
+        .unwrap()
+        .program_id
+        .into()
+}
+
+// a mint whose transfer hook is managed by the admin if `authority` is its `Authority`
+async fn create_mint_with_transfer_hook(
+    env: &mut Env,
+    admin: &Keypair,
+    base_coin: &BaseCoin,
+    authority: impl FnOnce(&Pubkey) -> Pubkey,
+) -> Pubkey {
+    let mint = Keypair::new();
+    let mut args = default_args(&admin.pubkey(), base_coin);
+    args.transfer_hook_args = Some(TransferHookArgs {
+        program_id: TRANSFER_HOOK_ID,
+        authority: authority(&mint.pubkey()),
+    });
+    let ix = instructions::create_mint(
+        &admin.pubkey(),
+        &mint.pubkey(),
+        &base_coin.token_program,
+        args,
+    );
+    env.send(&[ix], &[admin, &mint]).await.unwrap();
+    env.seed_transfer_hook(&mint.pubkey()).await;
+    mint.pubkey()
+}
+
+#[tokio::test]
+async fn creator_token_transfers_invoke_transfer_hook() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let holder = Pubkey::new_unique();
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let mint =
+        create_mint_with_transfer_hook(&mut env, &admin, &base_coin, |mint| pda::authority(mint).0)
+            .await;
+    assert_eq!(
+        transfer_hook_program(&mut env, &mint).await,
+        Some(TRANSFER_HOOK_ID)
+    );
+    env.fund_base_coin(&base_coin, &admin.pubkey(), 1_000_000)
+        .await;
+
+    // issuing mints rather than transfers
+    env.issue(&admin, &mint, &base_coin, 1_000_000)
+        .await
+        .unwrap();
+    assert_eq!(env.transfer_hook_invocations(&mint).await, 0);
+    env.transfer_mint(&admin, &holder, &mint, 10_000_000).await;
+    assert_eq!(env.transfer_hook_invocations(&mint).await, 1);
+
+    // fees paid out in the mint are transferred to the fee collector
+    env.send(
+        &[instructions::change_fee_payout(
+            &admin.pubkey(),
+            &mint,
+            FeePayout::Mint,
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    env.withdraw_fees(&mint, &base_coin, &[mint_token_account(&holder, &mint)])
+        .await
+        .unwrap();
+    assert_eq!(env.transfer_hook_invocations(&mint).await, 2);
+
+    // without a hook program, transfers need no extra accounts
+    env.send(
+        &[instructions::change_transfer_hook(
+            &admin.pubkey(),
+            &mint,
+            None,
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    assert_eq!(transfer_hook_program(&mut env, &mint).await, None);
+    env.transfer_mint(&admin, &holder, &mint, 10_000_000).await;
+    assert_eq!(env.transfer_hook_invocations(&mint).await, 2);
+}
+
+#[tokio::test]
+async fn change_transfer_hook_rejects_hooks_of_other_authorities() {
+    let mut env = Env::new().await;
+    let admin = env.create_user().await;
+    let base_coin = env.create_base_coin(spl_token::ID).await;
+    let admin_key = admin.pubkey();
+    let mints = [
+        create_mint_with_transfer_hook(&mut env, &admin, &base_coin, |_| admin_key).await,
+        env.create_mint(&admin, &base_coin).await,
+    ];
+
+    for mint in mints {
+        let result = env
+            .send(
+                &[instructions::change_transfer_hook(
+                    &admin.pubkey(),
+                    &mint,
+                    Some(TRANSFER_HOOK_ID),
+                )],
+                &[&admin],
+            )
+            .await;
+        assert_error(result, CustomError::IncorrectTransferHookAuthority);
+    }
+}
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum LoyaltyError {
+    IncorrectUpdateAuthority,
+    IncorrectMint,
+    IncorrectTransferHook,
+    NotTransferring,
+    PointsBasisPtsCannotExceed10000,
+    InsufficientPoints,
+    MathOverflow,
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        extension::{
+            transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state,
+    },
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::{
+    error::LoyaltyError,
+    state::{LoyaltyConfig, Points},
+};
+
+#[derive(Accounts)]
+pub struct ExecuteCtx<'info> {
+    #[account(
+        token::mint = mint,
+    )]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        token::mint = mint,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Owner or delegate of the source, checked by the token program
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: Address checked by seeds
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"loyalty_config", mint.key().as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, LoyaltyConfig>,
+    #[account(
+        seeds = [b"merchant", mint.key().as_ref(), destination.owner.as_ref()],
+        bump,
+    )]
+    /// CHECK: Only exists if the destination's owner is a registered merchant
+    pub merchant: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"points", mint.key().as_ref(), source.owner.as_ref()],
+        bump,
+    )]
+    /// CHECK: Only exists if the source's owner opened a points account
+    pub points: UncheckedAccount<'info>,
+}
+
+pub fn execute_handler(ctx: Context<ExecuteCtx>, amount: u64) -> Result<()> {
+    // only the token program sets the flag, for the duration of the transfer
+    {
+        let source_info = ctx.accounts.source.to_account_info();
+        let source_data = source_info.try_borrow_data()?;
+        let source = StateWithExtensions::<state::Account>::unpack(&source_data)?;
+        let transferring = source
+            .get_extension::<TransferHookAccount>()
+            .is_ok_and(|extension| bool::from(extension.transferring));
+        require!(transferring, LoyaltyError::NotTransferring);
+    }
+
+    // transfers never fail for lack of a points account, they just earn nothing
+    if ctx.accounts.merchant.owner != &crate::ID || ctx.accounts.points.owner != &crate::ID {
+        return Ok(());
+    }
+
+    let earned = u64::try_from(
+        u128::from(amount) * u128::from(ctx.accounts.config.points_basis_pts) / 10_000,
+    )
+    .map_err(|_| LoyaltyError::MathOverflow)?;
+    let mut data = ctx.accounts.points.try_borrow_mut_data()?;
+    let mut points = Points::try_deserialize(&mut &data[..])?;
+    points.balance = points
+        .balance
+        .checked_add(earned)
+        .ok_or(LoyaltyError::MathOverflow)?;
+    points.earned = points
+        .earned
+        .checked_add(earned)
+        .ok_or(LoyaltyError::MathOverflow)?;
+    points.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
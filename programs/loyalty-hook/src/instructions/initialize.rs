@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        extension::{transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions},
+        state,
+    },
+    token_interface::Mint,
+};
+use candy_wrapper::state::Authority;
+use spl_tlv_account_resolution::{
+    account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
+};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+use crate::{
+    error::LoyaltyError,
+    state::{LoyaltyConfig, LOYALTY_CONFIG_SPACE},
+};
+
+// the config, the merchant account of the destination's owner and the points account of the
+// source's owner
+pub const EXTRA_ACCOUNT_METAS_LEN: usize = 3;
+
+#[derive(Accounts)]
+pub struct InitializeCtx<'info> {
+    #[account(
+        mut,
+        constraint = payer.key() == authority.load()?.admin @LoyaltyError::IncorrectUpdateAuthority,
+    )]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [b"authority", mint.key().as_ref()],
+        bump = authority.load()?.bump,
+        seeds::program = candy_wrapper::ID,
+    )]
+    pub authority: AccountLoader<'info, Authority>,
+    #[account(
+        constraint = mint.key() == authority.load()?.mint @LoyaltyError::IncorrectMint,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        space = ExtraAccountMetaList::size_of(EXTRA_ACCOUNT_METAS_LEN)?,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: Written as an `ExtraAccountMetaList`
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = LOYALTY_CONFIG_SPACE,
+        seeds = [b"loyalty_config", mint.key().as_ref()],
+        bump,
+    )]
+    pub config: Account<'info, LoyaltyConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+fn extra_account_metas() -> Result<[ExtraAccountMeta; EXTRA_ACCOUNT_METAS_LEN]> {
+    // accounts of the transfer are source, mint, destination and owner, in that order, and the
+    // owner of a token account is stored at offset 32
+    Ok([
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"loyalty_config".to_vec(),
+                },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            false,
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"merchant".to_vec(),
+                },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountData {
+                    account_index: 2,
+                    data_index: 32,
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"points".to_vec(),
+                },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountData {
+                    account_index: 0,
+                    data_index: 32,
+                    length: 32,
+                },
+            ],
+            false,
+            true,
+        )?,
+    ])
+}
+
+pub fn initialize_handler(ctx: Context<InitializeCtx>, points_basis_pts: u16) -> Result<()> {
+    require!(
+        points_basis_pts <= 10_000,
+        LoyaltyError::PointsBasisPtsCannotExceed10000
+    );
+
+    // transfers of the mint must be routed through this program
+    {
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<state::Mint>::unpack(&mint_data)?;
+        let hook_program = mint
+            .get_extension::<TransferHook>()
+            .ok()
+            .and_then(|transfer_hook| Option::<Pubkey>::from(transfer_hook.program_id));
+        require!(
+            hook_program == Some(crate::ID),
+            LoyaltyError::IncorrectTransferHook
+        );
+    }
+
+    let config = &mut ctx.accounts.config;
+    config.bump = ctx.bumps.config;
+    config.mint = ctx.accounts.mint.key();
+    config.points_basis_pts = points_basis_pts;
+
+    let mut data = ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?;
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas()?)?;
+    Ok(())
+}
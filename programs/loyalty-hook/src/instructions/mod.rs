@@ -0,0 +1,13 @@
+pub mod execute;
+pub mod initialize;
+pub mod open_points;
+pub mod redeem_points;
+pub mod register_merchant;
+pub mod remove_merchant;
+
+pub use execute::*;
+pub use initialize::*;
+pub use open_points::*;
+pub use redeem_points::*;
+pub use register_merchant::*;
+pub use remove_merchant::*;
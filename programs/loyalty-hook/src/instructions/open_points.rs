@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::{LoyaltyConfig, Points, POINTS_SPACE};
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct OpenPointsCtx<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"loyalty_config", mint.key().as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, LoyaltyConfig>,
+    #[account(
+        init,
+        payer = payer,
+        space = POINTS_SPACE,
+        seeds = [b"points", mint.key().as_ref(), wallet.as_ref()],
+        bump,
+    )]
+    pub points: Account<'info, Points>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn open_points_handler(ctx: Context<OpenPointsCtx>, wallet: Pubkey) -> Result<()> {
+    let points = &mut ctx.accounts.points;
+    points.bump = ctx.bumps.points;
+    points.mint = ctx.accounts.mint.key();
+    points.wallet = wallet;
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    error::LoyaltyError,
+    state::{Merchant, Points},
+};
+
+#[derive(Accounts)]
+pub struct RedeemPointsCtx<'info> {
+    pub wallet: Signer<'info>,
+    // the merchant granting the reward co-signs
+    pub merchant_wallet: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"merchant", mint.key().as_ref(), merchant_wallet.key().as_ref()],
+        bump = merchant.bump,
+    )]
+    pub merchant: Account<'info, Merchant>,
+    #[account(
+        mut,
+        seeds = [b"points", mint.key().as_ref(), wallet.key().as_ref()],
+        bump = points.bump,
+    )]
+    pub points: Account<'info, Points>,
+}
+
+pub fn redeem_points_handler(ctx: Context<RedeemPointsCtx>, amount: u64) -> Result<()> {
+    let points = &mut ctx.accounts.points;
+    points.balance = points
+        .balance
+        .checked_sub(amount)
+        .ok_or(LoyaltyError::InsufficientPoints)?;
+    Ok(())
+}
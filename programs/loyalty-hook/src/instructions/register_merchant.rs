@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use candy_wrapper::state::Authority;
+
+use crate::{
+    error::LoyaltyError,
+    state::{LoyaltyConfig, Merchant, MERCHANT_SPACE},
+};
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct RegisterMerchantCtx<'info> {
+    #[account(
+        mut,
+        constraint = payer.key() == authority.load()?.admin @LoyaltyError::IncorrectUpdateAuthority,
+    )]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [b"authority", mint.key().as_ref()],
+        bump = authority.load()?.bump,
+        seeds::program = candy_wrapper::ID,
+    )]
+    pub authority: AccountLoader<'info, Authority>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"loyalty_config", mint.key().as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, LoyaltyConfig>,
+    #[account(
+        init,
+        payer = payer,
+        space = MERCHANT_SPACE,
+        seeds = [b"merchant", mint.key().as_ref(), wallet.as_ref()],
+        bump,
+    )]
+    pub merchant: Account<'info, Merchant>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_merchant_handler(ctx: Context<RegisterMerchantCtx>, wallet: Pubkey) -> Result<()> {
+    let merchant = &mut ctx.accounts.merchant;
+    merchant.bump = ctx.bumps.merchant;
+    merchant.mint = ctx.accounts.mint.key();
+    merchant.wallet = wallet;
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use candy_wrapper::state::Authority;
+
+use crate::{error::LoyaltyError, state::Merchant};
+
+#[derive(Accounts)]
+pub struct RemoveMerchantCtx<'info> {
+    #[account(
+        mut,
+        constraint = payer.key() == authority.load()?.admin @LoyaltyError::IncorrectUpdateAuthority,
+    )]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [b"authority", mint.key().as_ref()],
+        bump = authority.load()?.bump,
+        seeds::program = candy_wrapper::ID,
+    )]
+    pub authority: AccountLoader<'info, Authority>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"merchant", mint.key().as_ref(), merchant.wallet.as_ref()],
+        bump = merchant.bump,
+    )]
+    pub merchant: Account<'info, Merchant>,
+}
+
+pub fn remove_merchant_handler(_ctx: Context<RemoveMerchantCtx>) -> Result<()> {
+    Ok(())
+}
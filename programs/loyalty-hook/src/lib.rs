@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+declare_id!("31T9fuyARBtkCVm9EqGjLvn7U11vUz7HQHw2xHVVVpNx");
+
+pub mod error;
+pub mod instructions;
+pub mod state;
+
+use instructions::*;
+#[program]
+pub mod loyalty_hook {
+
+    use super::*;
+
+    pub fn initialize(ctx: Context<InitializeCtx>, points_basis_pts: u16) -> Result<()> {
+        instructions::initialize::initialize_handler(ctx, points_basis_pts)
+    }
+
+    pub fn register_merchant(ctx: Context<RegisterMerchantCtx>, wallet: Pubkey) -> Result<()> {
+        instructions::register_merchant::register_merchant_handler(ctx, wallet)
+    }
+
+    pub fn remove_merchant(ctx: Context<RemoveMerchantCtx>) -> Result<()> {
+        instructions::remove_merchant::remove_merchant_handler(ctx)
+    }
+
+    pub fn open_points(ctx: Context<OpenPointsCtx>, wallet: Pubkey) -> Result<()> {
+        instructions::open_points::open_points_handler(ctx, wallet)
+    }
+
+    pub fn redeem_points(ctx: Context<RedeemPointsCtx>, amount: u64) -> Result<()> {
+        instructions::redeem_points::redeem_points_handler(ctx, amount)
+    }
+
+    #[interface(spl_transfer_hook_interface::execute)]
+    pub fn execute(ctx: Context<ExecuteCtx>, amount: u64) -> Result<()> {
+        instructions::execute::execute_handler(ctx, amount)
+    }
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct LoyaltyConfig {
+    pub bump: u8,
+    pub mint: Pubkey,
+    // points credited per token spent at a merchant, 10_000 for one point per token
+    pub points_basis_pts: u16,
+}
+pub const LOYALTY_CONFIG_SPACE: usize = 8 + 1 + 32 + 2;
+
+#[account]
+pub struct Merchant {
+    pub bump: u8,
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+}
+pub const MERCHANT_SPACE: usize = 8 + 1 + 32 + 32;
+
+#[account]
+pub struct Points {
+    pub bump: u8,
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+    pub balance: u64,
+    // points credited over the account's lifetime, never reduced by redemptions
+    pub earned: u64,
+}
+pub const POINTS_SPACE: usize = 8 + 1 + 32 + 32 + 8 + 8;
@@ -2,7 +2,6 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
-  ExtensionType,
   LENGTH_SIZE,
   TOKEN_2022_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
@@ -10,7 +9,6 @@ import {
   burnChecked,
   createMint,
   getAssociatedTokenAddressSync,
-  getMintLen,
   getOrCreateAssociatedTokenAccount,
   harvestWithheldTokensToMint,
   mintTo,
@@ -21,10 +19,10 @@ import {
   Keypair,
   LAMPORTS_PER_SOL,
   PublicKey,
-  SystemProgram,
   Transaction,
   sendAndConfirmTransaction,
 } from "@solana/web3.js";
+import { assert } from "chai";
 import { CandyWrapper } from "../target/types/candy_wrapper";
 
 describe("candy-wrapper", () => {
@@ -45,6 +43,10 @@ describe("candy-wrapper", () => {
     [Buffer.from("authority"), mint.toBuffer()],
     program.programId
   );
+  const [feeVault] = PublicKey.findProgramAddressSync(
+    [Buffer.from("fee_vault"), mint.toBuffer()],
+    program.programId
+  );
   let USDC: PublicKey;
   let authorityBaseTokenAccount;
   let authorityMintTokenAccount;
@@ -96,14 +98,6 @@ describe("candy-wrapper", () => {
   });
 
   it("Create Mint!", async () => {
-    // these two are compulsory other extensions are optional
-    const mintLen = getMintLen([
-      ExtensionType.TransferFeeConfig,
-      ExtensionType.MetadataPointer,
-    ]);
-    const lamports = await connection.getMinimumBalanceForRentExemption(
-      mintLen
-    );
     const protocolBaseCoinTokenAccount =
       await getOrCreateAssociatedTokenAccount(
         connection,
@@ -112,14 +106,8 @@ describe("candy-wrapper", () => {
         wallet.publicKey,
         false
       );
-    const ix1 = SystemProgram.createAccount({
-      fromPubkey: wallet.publicKey,
-      newAccountPubkey: mint,
-      space: mintLen,
-      lamports,
-      programId: TOKEN_2022_PROGRAM_ID,
-    });
-    const ix2 = await program.methods
+    // the program allocates the mint, which signs as no seed is given
+    const ix = await program.methods
       .createMint({
         admin: wallet.publicKey,
         mintToBaseRatio: 69,
@@ -131,16 +119,26 @@ describe("candy-wrapper", () => {
           feeBasisPts: 5,
           maxFee: new anchor.BN(Number.MAX_SAFE_INTEGER),
         },
+        transferHookArgs: null,
+        giftCard: false,
+        blocklist: false,
+        freezable: false,
+        defaultFrozen: false,
+        nonTransferable: false,
+        mintSeed: null,
       })
       .accounts({
         mint: mint,
         baseCoin: USDC,
         payer: wallet.publicKey,
         protocolBaseCoinTokenAccount: protocolBaseCoinTokenAccount.address,
+        extraAccountMetaList: null,
+        merchantRegistry: null,
         tokenProgramBaseCoin: TOKEN_PROGRAM_ID,
       })
       .instruction();
-    const transaction = new Transaction().add(ix1).add(ix2);
+    ix.keys.find((key) => key.pubkey.equals(mint))!.isSigner = true;
+    const transaction = new Transaction().add(ix);
     const txSig = await sendAndConfirmTransaction(
       provider.connection,
       transaction,
@@ -201,33 +199,22 @@ describe("candy-wrapper", () => {
       false,
       TOKEN_2022_PROGRAM_ID
     );
-    const feeCollectorBaseCoinTokenAccount = getAssociatedTokenAddressSync(
-      USDC,
-      wallet.publicKey,
-      true
-    );
-    const protocolBaseCoinTokenAccount =
-      await getOrCreateAssociatedTokenAccount(
-        connection,
-        wallet.payer,
-        USDC,
-        wallet.publicKey,
-        false
-      );
     const ix = await program.methods
       .issueMint(new anchor.BN(1 * 10 ** 6))
       .accounts({
         mint: mint,
         payer: wallet.publicKey,
         baseCoin: USDC,
-        protocolBaseCoinTokenAccount: protocolBaseCoinTokenAccount.address,
         authorityBaseCoinTokenAccount: authorityBaseTokenAccount,
         payerMintTokenAccount: payerMintTokenAccount,
         payerBaseCoinTokenAccount: payerBaseTokenAccount,
-        feeCollectorBaseCoinTokenAccount: feeCollectorBaseCoinTokenAccount,
         tokenProgramBaseCoin: TOKEN_PROGRAM_ID,
       })
       .instruction();
+    // issuance must not write lock the authority so it can run in parallel with itself
+    assert.isFalse(
+      ix.keys.find((key) => key.pubkey.equals(authority)).isWritable
+    );
 
     const transaction = new Transaction().add(ix);
     const txSig = await sendAndConfirmTransaction(
@@ -303,33 +290,22 @@ describe("candy-wrapper", () => {
       true,
       TOKEN_2022_PROGRAM_ID
     );
-    const feeCollectorBaseCoinTokenAccount = getAssociatedTokenAddressSync(
-      USDC,
-      wallet.publicKey
-    );
-    const protocolBaseCoinTokenAccount =
-      await getOrCreateAssociatedTokenAccount(
-        connection,
-        wallet.payer,
-        USDC,
-        wallet.publicKey,
-        false
-      );
-
     const ix = await program.methods
       .redeemBasecoin(new anchor.BN((1 * (9995 / 10000) - 0.1) * 10 ** 6))
       .accounts({
         mint: mint,
         payer: wallet.publicKey,
         baseCoin: USDC,
-        protocolBaseCoinTokenAccount: protocolBaseCoinTokenAccount.address,
         authorityBaseCoinTokenAccount: authorityBaseTokenAccount,
         payerMintTokenAccount: payerMintTokenAccount,
         payerBaseCoinTokenAccount: payerBaseTokenAccount,
-        feeCollectorBaseCoinTokenAccount: feeCollectorBaseCoinTokenAccount,
         tokenProgramBaseCoin: TOKEN_PROGRAM_ID,
       })
       .instruction();
+    // redemption must not write lock the authority so it can run in parallel with itself
+    assert.isFalse(
+      ix.keys.find((key) => key.pubkey.equals(authority)).isWritable
+    );
 
     const transaction = new Transaction().add(ix);
     const txSig = await sendAndConfirmTransaction(
@@ -365,15 +341,6 @@ describe("candy-wrapper", () => {
   });
 
   it("Withdraw to fee collector", async () => {
-    const protocolBaseCoinTokenAccount =
-      await getOrCreateAssociatedTokenAccount(
-        connection,
-        wallet.payer,
-        USDC,
-        wallet.publicKey,
-        false
-      );
-
     const feeCollectorBaseCoinTokenAccount = getAssociatedTokenAddressSync(
       USDC,
       wallet.publicKey
@@ -386,7 +353,6 @@ describe("candy-wrapper", () => {
         mint: mint,
         baseCoin: USDC,
         feeCollectorBaseCoinTokenAccount: feeCollectorBaseCoinTokenAccount,
-        protocolBaseCoinTokenAccount: protocolBaseCoinTokenAccount.address,
         authorityMintTokenAccount: authorityMintTokenAccount,
         authorityBaseCoinTokenAccount: authorityBaseTokenAccount,
         tokenProgramBaseCoin: TOKEN_PROGRAM_ID,
@@ -404,6 +370,111 @@ describe("candy-wrapper", () => {
     console.log(await program.account.authority.fetch(authority));
   });
 
+  it("Harvest and withdraw fees", async () => {
+    const holderMintTokenAccount = getAssociatedTokenAddressSync(
+      mint,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const feeCollectorBaseCoinTokenAccount = getAssociatedTokenAddressSync(
+      USDC,
+      wallet.publicKey
+    );
+
+    const ix = await program.methods
+      .harvestAndWithdrawFees()
+      .accounts({
+        payer: wallet.publicKey,
+        mint: mint,
+        baseCoin: USDC,
+        feeCollectorBaseCoinTokenAccount: feeCollectorBaseCoinTokenAccount,
+        authorityMintTokenAccount: authorityMintTokenAccount,
+        authorityBaseCoinTokenAccount: authorityBaseTokenAccount,
+        tokenProgramBaseCoin: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: holderMintTokenAccount, isSigner: false, isWritable: true },
+      ])
+      .instruction();
+    const transaction = new Transaction().add(ix);
+    const txSig = await sendAndConfirmTransaction(
+      provider.connection,
+      transaction,
+      [wallet.payer],
+      { skipPreflight: true }
+    );
+    console.log(`Transaction Signature: ${txSig}`);
+
+    console.log(await program.account.authority.fetch(authority));
+  });
+
+  it("Withdraw fees in mint", async () => {
+    await program.methods
+      .changeFeePayout({ mint: {} })
+      .accounts({ authority: authority, payer: wallet.publicKey })
+      .rpc();
+
+    const feeCollectorBaseCoinTokenAccount = getAssociatedTokenAddressSync(
+      USDC,
+      wallet.publicKey
+    );
+    const feeCollectorMintTokenAccount = getAssociatedTokenAddressSync(
+      mint,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const txSig = await program.methods
+      .withdrawFees()
+      .accounts({
+        payer: wallet.publicKey,
+        mint: mint,
+        baseCoin: USDC,
+        feeCollectorBaseCoinTokenAccount: feeCollectorBaseCoinTokenAccount,
+        feeCollectorMintTokenAccount: feeCollectorMintTokenAccount,
+        authorityMintTokenAccount: authorityMintTokenAccount,
+        authorityBaseCoinTokenAccount: authorityBaseTokenAccount,
+        tokenProgramBaseCoin: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ skipPreflight: true });
+    console.log(`Transaction Signature: ${txSig}`);
+
+    await program.methods
+      .changeFeePayout({ baseCoin: {} })
+      .accounts({ authority: authority, payer: wallet.publicKey })
+      .rpc();
+  });
+
+  it("Claim protocol fees", async () => {
+    const protocolBaseCoinTokenAccount = getAssociatedTokenAddressSync(
+      USDC,
+      wallet.publicKey
+    );
+
+    const txSig = await program.methods
+      .claimProtocolFees(1)
+      .accounts({ payer: wallet.publicKey })
+      .remainingAccounts([
+        { pubkey: authority, isSigner: false, isWritable: true },
+        { pubkey: feeVault, isSigner: false, isWritable: true },
+        { pubkey: USDC, isSigner: false, isWritable: false },
+        {
+          pubkey: protocolBaseCoinTokenAccount,
+          isSigner: false,
+          isWritable: true,
+        },
+        { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      ])
+      .rpc({ skipPreflight: true });
+    console.log(`Transaction Signature: ${txSig}`);
+
+    console.log(
+      (await program.account.authority.fetch(authority)).protocolFeesAccrued
+    );
+  });
+
   it("Change Fee Collector", async () => {
     const txSig = await program.methods
       .changeFeeCollector(recipient)
@@ -452,6 +523,57 @@ describe("candy-wrapper", () => {
     console.log(`Transaction Signature: ${txSig}`);
   });
 
+  it("Set Fee Split", async () => {
+    const txSig = await program.methods
+      .setFeeSplit([
+        { wallet: wallet.publicKey, basisPts: 7000 },
+        { wallet: recipient, basisPts: 3000 },
+      ])
+      .accounts({
+        payer: wallet.publicKey,
+        mint: mint,
+        baseCoin: USDC,
+        tokenProgramBaseCoin: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    console.log(`Transaction Signature: ${txSig}`);
+
+    console.log(
+      (await program.account.authority.fetch(authority)).feeCollector
+    );
+  });
+
+  it("Distribute", async () => {
+    const [feeSplit] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_split"), mint.toBuffer()],
+      program.programId
+    );
+    const recipientTokenAccounts = await Promise.all(
+      [wallet.publicKey, recipient].map((owner) =>
+        getOrCreateAssociatedTokenAccount(connection, wallet.payer, USDC, owner)
+      )
+    );
+
+    const txSig = await program.methods
+      .distribute()
+      .accounts({
+        feeSplit: feeSplit,
+        baseCoin: USDC,
+        tokenProgramBaseCoin: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        recipientTokenAccounts.map((tokenAccount) => ({
+          pubkey: tokenAccount.address,
+          isSigner: false,
+          isWritable: true,
+        }))
+      )
+      .rpc();
+
+    console.log(`Transaction Signature: ${txSig}`);
+  });
+
   it("Set To Immutable", async () => {
     const txSig = await program.methods
       .setFeesToImmutable()